@@ -0,0 +1,111 @@
+use {
+    anyhow::Result,
+    clap::ValueEnum,
+    program_authority_client::HandoverEvent,
+    solana_sdk::pubkey::Pubkey,
+    std::path::Path,
+};
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    Csv,
+    Json,
+}
+
+const CSV_HEADER: &str = "program,slot,block_time,signature,program_id,instruction,\
+                          current_authority,new_authority,next_authority,timestamp,nonce,memo,\
+                          success";
+
+/// Reads an export of `getTransaction` results with the `json` encoding : an array of results, a
+/// single result or a whole JSON-RPC response
+pub fn read_transactions_file(path: &Path) -> Result<Vec<serde_json::Value>> {
+    let contents: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    Ok(match contents {
+        serde_json::Value::Array(transactions) => transactions,
+        serde_json::Value::Object(mut response) if response.contains_key("result") => {
+            vec![response.remove("result").unwrap()]
+        }
+        transaction => vec![transaction],
+    })
+}
+
+/// Handovers grouped by program and in chronological order, only those of `program` if it is set
+pub fn history_report(
+    mut events: Vec<HandoverEvent>,
+    program: Option<&Pubkey>,
+    format: ReportFormat,
+) -> Result<String> {
+    if let Some(program) = program {
+        events.retain(|event| event.handover.program_account == *program);
+    }
+    // The sort is stable, so transactions of the same slot and instructions of the same
+    // transaction keep their order
+    events.sort_by_key(|event| (event.handover.program_account, event.slot));
+
+    match format {
+        ReportFormat::Csv => {
+            let mut lines = vec![CSV_HEADER.to_string()];
+            lines.extend(events.iter().map(|event| {
+                [
+                    event.handover.program_account.to_string(),
+                    event.slot.to_string(),
+                    optional(event.block_time),
+                    event.signature.clone(),
+                    event.handover.program_id.to_string(),
+                    event.handover.instruction.to_string(),
+                    optional(event.handover.current_authority),
+                    event.handover.new_authority.to_string(),
+                    optional(event.handover.next_authority),
+                    optional(event.handover.timestamp),
+                    optional(event.handover.nonce),
+                    csv_field(event.handover.memo.as_deref().unwrap_or_default()),
+                    event.success.to_string(),
+                ]
+                .join(",")
+            }));
+            Ok(lines.join("\n"))
+        }
+        ReportFormat::Json => {
+            let events: Vec<serde_json::Value> = events
+                .iter()
+                .map(|event| {
+                    serde_json::json!({
+                        "program": event.handover.program_account.to_string(),
+                        "slot": event.slot,
+                        "block_time": event.block_time,
+                        "signature": event.signature,
+                        "program_id": event.handover.program_id.to_string(),
+                        "instruction": event.handover.instruction,
+                        "current_authority": event
+                            .handover
+                            .current_authority
+                            .map(|authority| authority.to_string()),
+                        "new_authority": event.handover.new_authority.to_string(),
+                        "next_authority": event
+                            .handover
+                            .next_authority
+                            .map(|authority| authority.to_string()),
+                        "timestamp": event.handover.timestamp,
+                        "nonce": event.handover.nonce,
+                        "memo": event.handover.memo,
+                        "success": event.success,
+                    })
+                })
+                .collect();
+            Ok(serde_json::to_string_pretty(&events)?)
+        }
+    }
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+/// Quotes memos containing separators, quotes or line breaks
+fn csv_field(value: &str) -> String {
+    if value.contains(|c: char| matches!(c, ',' | '"' | '\n' | '\r')) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,482 @@
+#![deny(warnings)]
+
+use {
+    anyhow::{
+        anyhow,
+        Result,
+    },
+    clap::{
+        Parser,
+        Subcommand,
+        ValueEnum,
+    },
+    program_authority_client::{
+        encode,
+        with_durable_nonce,
+        Encoding,
+    },
+    solana_sdk::{
+        hash::Hash,
+        instruction::Instruction,
+        message::Message,
+        pubkey::Pubkey,
+        signature::Keypair,
+        transaction::Transaction,
+    },
+    std::path::PathBuf,
+};
+
+mod history;
+mod offline;
+mod plan;
+mod signer;
+#[cfg(feature = "simulate")]
+mod simulate;
+mod status;
+
+#[cfg(feature = "simulate")]
+pub use simulate::{
+    decode_instruction,
+    describe_simulation,
+    run_simulation,
+    simulate_handover,
+    Simulation,
+};
+pub use {
+    history::{
+        history_report,
+        read_transactions_file,
+        ReportFormat,
+    },
+    offline::{
+        read_transaction_file,
+        sign_transaction_file,
+        write_transaction_file,
+    },
+    plan::{
+        action_transaction,
+        check_signers,
+        describe_plan,
+        Action,
+        Handover,
+        HandoverMode,
+        Plan,
+        Step,
+    },
+    signer::SignerSource,
+    status::{
+        describe_status,
+        inspect_program_data,
+        read_account_dump,
+        AddressBook,
+    },
+};
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Parser, Debug)]
+#[clap(
+    name = "program-authority",
+    about = "Moves the upgrade authority of a program through the escrow or the timelock"
+)]
+pub struct Cli {
+    /// RPC URL of the cluster
+    #[clap(
+        long,
+        short = 'u',
+        global = true,
+        default_value = "http://localhost:8899"
+    )]
+    pub url:             String,
+    /// Keypair file of the account paying the transaction fees, or its public key when exporting
+    #[clap(
+        long,
+        global = true,
+        default_value = "~/.config/solana/id.json",
+        value_parser
+    )]
+    pub fee_payer:       SignerSource,
+    /// Print the instructions, the message or the partially signed transaction instead of sending
+    #[clap(long, global = true, value_enum)]
+    pub export:          Option<ExportFormat>,
+    /// Run the instruction in a local bank with copies of the program accounts and print the
+    /// upgrade authority before and after instead of sending, needs the `simulate` feature
+    #[clap(long, global = true, conflicts_with = "export")]
+    pub simulate:        bool,
+    /// Encoding of exported data and transaction files, base58 or base64
+    #[clap(long, global = true, default_value = "base64", value_parser)]
+    pub encoding:        Encoding,
+    /// Durable nonce account whose nonce replaces the recent blockhash
+    #[clap(long, global = true, value_parser)]
+    pub nonce:           Option<Pubkey>,
+    /// Authority of the durable nonce account, defaults to the fee payer
+    #[clap(long, global = true, value_parser)]
+    pub nonce_authority: Option<SignerSource>,
+    /// Blockhash or durable nonce to use instead of fetching it from the cluster
+    #[clap(long, global = true, value_parser)]
+    pub blockhash:       Option<Hash>,
+    #[clap(subcommand)]
+    pub command:         Command,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One serialized `Instruction` per line
+    Instructions,
+    /// Unsigned `Message`
+    Message,
+    /// `Transaction` signed by the keypair files that were given, to finish with `sign`
+    Transaction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    #[clap(subcommand)]
+    Escrow(EscrowCommand),
+    #[clap(subcommand)]
+    Timelock(TimelockCommand),
+    /// Adds signatures to a partially signed transaction file
+    Sign {
+        #[clap(long)]
+        transaction: PathBuf,
+        /// Keypair files to sign with
+        #[clap(long = "keypair", required = true)]
+        keypairs:    Vec<PathBuf>,
+    },
+    /// Sends a fully signed transaction file
+    Submit {
+        #[clap(long)]
+        transaction: PathBuf,
+    },
+    /// Tells whether the upgrade authority of a program is a key, an escrow or a timelock
+    Status {
+        #[clap(long, value_parser)]
+        program:           Pubkey,
+        /// Authority to match the escrow and timelock addresses against
+        #[clap(long = "candidate", value_parser)]
+        candidates:        Vec<Pubkey>,
+        /// File with one candidate public key per line, optionally followed by a label
+        #[clap(long)]
+        address_book:      Option<PathBuf>,
+        /// Unlock timestamp to match the timelock addresses against
+        #[clap(long = "timestamp", value_parser)]
+        timestamps:        Vec<i64>,
+        /// Nonce to match the scoped escrow addresses against
+        #[clap(long = "scope-nonce", value_parser)]
+        nonces:            Vec<u64>,
+        /// Program data account saved with `solana account --output json`, instead of fetching it
+        #[clap(long)]
+        program_data_dump: Option<PathBuf>,
+    },
+    /// Prints the escrow and timelock handovers of programs, from the cluster or from exported
+    /// transactions
+    History {
+        /// Program whose history to read, required when reading from the cluster
+        #[clap(long, value_parser)]
+        program:      Option<Pubkey>,
+        /// JSON export of `getTransaction` results, instead of reading from the cluster
+        #[clap(long = "transactions")]
+        transactions: Vec<PathBuf>,
+        /// Number of the latest transactions to read from the cluster
+        #[clap(long, default_value = "1000")]
+        limit:        usize,
+        #[clap(long, value_enum, default_value = "csv")]
+        format:       ReportFormat,
+    },
+    /// Prints the instructions `apply` would send for a plan file and the keys that have to sign
+    Plan {
+        #[clap(long)]
+        file: PathBuf,
+    },
+    /// Sends the instructions of a plan file in order and checks the resulting upgrade authorities
+    Apply {
+        #[clap(long)]
+        file:     PathBuf,
+        /// Keypair files of the authorities that have to sign
+        #[clap(long = "keypair")]
+        keypairs: Vec<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum EscrowCommand {
+    /// Moves the upgrade authority to the escrow, signed by the current authority
+    Propose {
+        #[clap(long, value_parser)]
+        program:           Pubkey,
+        /// Keypair file of the current upgrade authority, or its public key when exporting
+        #[clap(long, value_parser)]
+        current_authority: SignerSource,
+        #[clap(long, value_parser)]
+        new_authority:     Pubkey,
+        #[clap(long)]
+        reason:            Option<String>,
+    },
+    /// Gives the upgrade authority back to the current authority
+    Revert {
+        #[clap(long, value_parser)]
+        program:           Pubkey,
+        /// Keypair file of the proposing authority, or its public key when exporting
+        #[clap(long, value_parser)]
+        current_authority: SignerSource,
+        #[clap(long, value_parser)]
+        new_authority:     Pubkey,
+        #[clap(long)]
+        reason:            Option<String>,
+    },
+    /// Takes the upgrade authority out of the escrow, signed by the new authority
+    Accept {
+        #[clap(long, value_parser)]
+        program:           Pubkey,
+        #[clap(long, value_parser)]
+        current_authority: Pubkey,
+        /// Keypair file of the new upgrade authority, or its public key when exporting
+        #[clap(long, value_parser)]
+        new_authority:     SignerSource,
+        #[clap(long)]
+        reason:            Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TimelockCommand {
+    /// Locks the upgrade authority until the timestamp, signed by the current authority
+    Commit {
+        #[clap(long, value_parser)]
+        program:           Pubkey,
+        /// Keypair file of the current upgrade authority, or its public key when exporting
+        #[clap(long, value_parser)]
+        current_authority: SignerSource,
+        #[clap(long, value_parser)]
+        new_authority:     Pubkey,
+        /// Unix timestamp after which the new authority can be transferred the program
+        #[clap(long, value_parser)]
+        timestamp:         i64,
+        #[clap(long)]
+        reason:            Option<String>,
+    },
+    /// Gives the upgrade authority to the new authority once the timestamp has passed
+    Transfer {
+        #[clap(long, value_parser)]
+        program:       Pubkey,
+        #[clap(long, value_parser)]
+        new_authority: Pubkey,
+        #[clap(long, value_parser)]
+        timestamp:     i64,
+        #[clap(long)]
+        reason:        Option<String>,
+    },
+}
+
+impl Cli {
+    /// Builds the instructions of the command, preceded by the nonce advance when `--nonce` is
+    /// set, along with the fee payer and the keypairs that are available to sign
+    pub fn instructions(&self) -> Result<(Vec<Instruction>, Pubkey, Vec<Keypair>)> {
+        let (fee_payer, fee_payer_keypair) = self.fee_payer.resolve()?;
+        let (instruction, mut keypairs) = self.command.prepare()?;
+        keypairs.extend(fee_payer_keypair);
+
+        let instructions = match self.nonce {
+            Some(nonce) => {
+                let nonce_authority = match &self.nonce_authority {
+                    Some(nonce_authority) => {
+                        let (nonce_authority, nonce_authority_keypair) =
+                            nonce_authority.resolve()?;
+                        keypairs.extend(nonce_authority_keypair);
+                        nonce_authority
+                    }
+                    None => fee_payer,
+                };
+                with_durable_nonce(&nonce, &nonce_authority, &[instruction])
+            }
+            None => vec![instruction],
+        };
+
+        Ok((instructions, fee_payer, keypairs))
+    }
+
+    /// Builds the message that the fee payer and the authorities sign
+    pub fn message(&self, recent_blockhash: Hash) -> Result<Message> {
+        let (instructions, fee_payer, _) = self.instructions()?;
+        Ok(Message::new_with_blockhash(
+            &instructions,
+            Some(&fee_payer),
+            &recent_blockhash,
+        ))
+    }
+
+    /// Builds the transaction and signs it with every keypair file that was given
+    pub fn build_transaction(&self, recent_blockhash: Hash) -> Result<Transaction> {
+        let (instructions, fee_payer, keypairs) = self.instructions()?;
+
+        let mut transaction = Transaction::new_unsigned(Message::new_with_blockhash(
+            &instructions,
+            Some(&fee_payer),
+            &recent_blockhash,
+        ));
+        transaction.try_partial_sign(&keypairs.iter().collect::<Vec<_>>(), recent_blockhash)?;
+        Ok(transaction)
+    }
+
+    /// Serializes the command in the `--export` format with the `--encoding` encoding
+    pub fn export(&self, format: ExportFormat, recent_blockhash: Hash) -> Result<String> {
+        match format {
+            ExportFormat::Instructions => {
+                let (instructions, _, _) = self.instructions()?;
+                Ok(instructions
+                    .iter()
+                    .map(|instruction| encode(instruction, self.encoding))
+                    .collect::<Vec<_>>()
+                    .join("\n"))
+            }
+            ExportFormat::Message => Ok(encode(&self.message(recent_blockhash)?, self.encoding)),
+            ExportFormat::Transaction => Ok(encode(
+                &self.build_transaction(recent_blockhash)?,
+                self.encoding,
+            )),
+        }
+    }
+}
+
+impl Command {
+    /// Builds the instruction for the command along with the keypairs that are available to sign it
+    pub fn prepare(&self) -> Result<(Instruction, Vec<Keypair>)> {
+        match self {
+            Command::Escrow(EscrowCommand::Propose {
+                program,
+                current_authority,
+                new_authority,
+                reason,
+            }) => {
+                let (current_authority, keypair) = current_authority.resolve()?;
+                let instruction = program_authority_client::propose_ix(
+                    &current_authority,
+                    new_authority,
+                    program,
+                    reason
+                        .clone()
+                        .map(program_authority_common::HandoverMemo::Reason),
+                );
+                Ok((instruction, keypair.into_iter().collect()))
+            }
+            Command::Escrow(EscrowCommand::Revert {
+                program,
+                current_authority,
+                new_authority,
+                reason,
+            }) => {
+                let (current_authority, keypair) = current_authority.resolve()?;
+                let instruction = program_authority_client::revert_ix(
+                    &current_authority,
+                    new_authority,
+                    program,
+                    reason
+                        .clone()
+                        .map(program_authority_common::HandoverMemo::Reason),
+                );
+                Ok((instruction, keypair.into_iter().collect()))
+            }
+            Command::Escrow(EscrowCommand::Accept {
+                program,
+                current_authority,
+                new_authority,
+                reason,
+            }) => {
+                let (new_authority, keypair) = new_authority.resolve()?;
+                let instruction = program_authority_client::accept_ix(
+                    current_authority,
+                    &new_authority,
+                    program,
+                    reason
+                        .clone()
+                        .map(program_authority_common::HandoverMemo::Reason),
+                );
+                Ok((instruction, keypair.into_iter().collect()))
+            }
+            Command::Timelock(TimelockCommand::Commit {
+                program,
+                current_authority,
+                new_authority,
+                timestamp,
+                reason,
+            }) => {
+                let (current_authority, keypair) = current_authority.resolve()?;
+                let instruction = program_authority_client::commit_ix(
+                    &current_authority,
+                    new_authority,
+                    program,
+                    *timestamp,
+                    reason
+                        .clone()
+                        .map(program_authority_common::HandoverMemo::Reason),
+                );
+                Ok((instruction, keypair.into_iter().collect()))
+            }
+            Command::Timelock(TimelockCommand::Transfer {
+                program,
+                new_authority,
+                timestamp,
+                reason,
+            }) => {
+                let instruction = program_authority_client::transfer_ix(
+                    new_authority,
+                    program,
+                    *timestamp,
+                    reason
+                        .clone()
+                        .map(program_authority_common::HandoverMemo::Reason),
+                );
+                Ok((instruction, vec![]))
+            }
+            Command::Sign { .. }
+            | Command::Submit { .. }
+            | Command::Status { .. }
+            | Command::History { .. }
+            | Command::Plan { .. }
+            | Command::Apply { .. } => Err(anyhow!("This command doesn't build an instruction")),
+        }
+    }
+
+    /// Describes the upgrade authority stored in the program data account of `status`, timelocks
+    /// have matured if `now` is past their timestamp
+    pub fn status(
+        &self,
+        address: &Pubkey,
+        owner: &Pubkey,
+        data: &[u8],
+        now: i64,
+    ) -> Result<String> {
+        match self {
+            Command::Status {
+                program,
+                candidates,
+                address_book,
+                timestamps,
+                nonces,
+                ..
+            } => {
+                let mut address_book = match address_book {
+                    Some(path) => AddressBook::read(path)?,
+                    None => AddressBook::default(),
+                };
+                candidates
+                    .iter()
+                    .for_each(|candidate| address_book.add(*candidate));
+
+                let status = inspect_program_data(
+                    program,
+                    address,
+                    owner,
+                    data,
+                    &address_book,
+                    timestamps,
+                    nonces,
+                    now,
+                )?;
+                Ok(describe_status(&status, &address_book))
+            }
+            _ => Err(anyhow!("This command doesn't inspect a program")),
+        }
+    }
+}
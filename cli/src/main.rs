@@ -0,0 +1,220 @@
+#[cfg(feature = "simulate")]
+use program_authority_cli::run_simulation;
+use {
+    anyhow::{
+        anyhow,
+        Result,
+    },
+    clap::Parser,
+    program_authority_cli::{
+        history_report,
+        read_account_dump,
+        read_transaction_file,
+        read_transactions_file,
+        sign_transaction_file,
+        Cli,
+        Command,
+        Plan,
+    },
+    program_authority_client::read_keypair,
+    solana_client::{
+        nonce_utils,
+        rpc_client::{
+            GetConfirmedSignaturesForAddress2Config,
+            RpcClient,
+        },
+        rpc_config::RpcTransactionConfig,
+        rpc_response::RpcConfirmedTransactionStatusWithSignature,
+    },
+    solana_sdk::{
+        commitment_config::CommitmentConfig,
+        pubkey::Pubkey,
+        signature::Signature,
+    },
+    solana_transaction_status::UiTransactionEncoding,
+    std::{
+        str::FromStr,
+        time::{
+            SystemTime,
+            UNIX_EPOCH,
+        },
+    },
+};
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let rpc_client = RpcClient::new_with_commitment(cli.url.clone(), CommitmentConfig::confirmed());
+
+    match &cli.command {
+        Command::Sign {
+            transaction,
+            keypairs,
+        } => {
+            let keypairs = keypairs
+                .iter()
+                .map(|path| read_keypair(path))
+                .collect::<Result<Vec<_>>>()?;
+            sign_transaction_file(transaction, cli.encoding, &keypairs)?;
+        }
+        Command::Submit { transaction } => {
+            let transaction = read_transaction_file(transaction, cli.encoding)?;
+            if !transaction.is_signed() {
+                return Err(anyhow!("Transaction is missing signatures"));
+            }
+            let signature = rpc_client.send_and_confirm_transaction(&transaction)?;
+            println!("{signature}");
+        }
+        Command::Status {
+            program,
+            program_data_dump,
+            ..
+        } => {
+            let (address, owner, data) = match program_data_dump {
+                Some(path) => read_account_dump(path)?,
+                None => {
+                    let address = program_authority_client::program_data_address(program);
+                    let account = rpc_client.get_account(&address)?;
+                    (address, account.owner, account.data)
+                }
+            };
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+            println!("{}", cli.command.status(&address, &owner, &data, now)?);
+        }
+        Command::History {
+            program,
+            transactions,
+            limit,
+            format,
+        } => {
+            let transactions = if transactions.is_empty() {
+                let program = program.ok_or_else(|| {
+                    anyhow!("Pass --program to read the history from the cluster")
+                })?;
+                get_transactions(&rpc_client, &program, *limit)?
+            } else {
+                let mut exported = vec![];
+                for path in transactions.iter() {
+                    exported.extend(read_transactions_file(path)?);
+                }
+                exported
+            };
+
+            let mut events = vec![];
+            for transaction in transactions {
+                events.extend(program_authority_client::decode_transaction(transaction)?);
+            }
+            println!("{}", history_report(events, program.as_ref(), *format)?);
+        }
+        Command::Plan { file } => {
+            println!("{}", Plan::read(file)?.describe(&rpc_client)?);
+        }
+        Command::Apply { file, keypairs } => {
+            let plan = Plan::read(file)?;
+            let fee_payer = match cli.fee_payer.resolve()? {
+                (_, Some(fee_payer)) => fee_payer,
+                (_, None) => return Err(anyhow!("apply needs the keypair file of the fee payer")),
+            };
+            let keypairs = keypairs
+                .iter()
+                .map(|path| read_keypair(path))
+                .collect::<Result<Vec<_>>>()?;
+            plan.apply(
+                &rpc_client,
+                &fee_payer,
+                &keypairs,
+                |handover, action, signature| {
+                    println!("{} {}: {signature}", handover.program, action.step)
+                },
+            )?;
+        }
+        Command::Escrow(_) | Command::Timelock(_) => {
+            let recent_blockhash = match (cli.blockhash, cli.nonce) {
+                (Some(blockhash), _) => blockhash,
+                (None, Some(nonce)) => {
+                    let nonce_account = nonce_utils::get_account_with_commitment(
+                        &rpc_client,
+                        &nonce,
+                        CommitmentConfig::confirmed(),
+                    )?;
+                    nonce_utils::data_from_account(&nonce_account)?.blockhash()
+                }
+                (None, None) => rpc_client.get_latest_blockhash()?,
+            };
+
+            match cli.export {
+                Some(format) => println!("{}", cli.export(format, recent_blockhash)?),
+                #[cfg(feature = "simulate")]
+                None if cli.simulate => {
+                    println!("{}", run_simulation(&cli, &rpc_client, recent_blockhash)?);
+                }
+                #[cfg(not(feature = "simulate"))]
+                None if cli.simulate => {
+                    return Err(anyhow!(
+                        "--simulate needs the CLI built with the simulate feature"
+                    ));
+                }
+                None => {
+                    let transaction = cli.build_transaction(recent_blockhash)?;
+                    if !transaction.is_signed() {
+                        return Err(anyhow!(
+                            "Transaction is missing signatures, pass keypair files or use --export"
+                        ));
+                    }
+                    let signature = rpc_client.send_and_confirm_transaction(&transaction)?;
+                    println!("{signature}");
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Latest transactions writing to the program data account, which every handover does, oldest
+/// first. The RPC returns at most 1000 signatures per call, older pages are fetched until `limit`
+/// is reached
+fn get_transactions(
+    rpc_client: &RpcClient,
+    program: &Pubkey,
+    limit: usize,
+) -> Result<Vec<serde_json::Value>> {
+    const MAX_SIGNATURES_PER_PAGE: usize = 1000;
+
+    let program_data = program_authority_client::program_data_address(program);
+    let mut signatures: Vec<RpcConfirmedTransactionStatusWithSignature> = vec![];
+    while signatures.len() < limit {
+        let page_limit = (limit - signatures.len()).min(MAX_SIGNATURES_PER_PAGE);
+        let page = rpc_client.get_signatures_for_address_with_config(
+            &program_data,
+            GetConfirmedSignaturesForAddress2Config {
+                before: signatures
+                    .last()
+                    .map(|status| Signature::from_str(&status.signature))
+                    .transpose()?,
+                limit: Some(page_limit),
+                ..GetConfirmedSignaturesForAddress2Config::default()
+            },
+        )?;
+        let last_page = page.len() < page_limit;
+        signatures.extend(page);
+        if last_page {
+            break;
+        }
+    }
+    // The RPC returns the newest signatures first, the report only sorts by slot and keeps the
+    // order of transactions within a slot
+    signatures.reverse();
+    signatures
+        .iter()
+        .map(|status| {
+            let transaction = rpc_client.get_transaction_with_config(
+                &Signature::from_str(&status.signature)?,
+                RpcTransactionConfig {
+                    encoding:                          Some(UiTransactionEncoding::Json),
+                    commitment:                        Some(rpc_client.commitment()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )?;
+            Ok(serde_json::to_value(transaction)?)
+        })
+        .collect()
+}
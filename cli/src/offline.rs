@@ -0,0 +1,34 @@
+use {
+    anyhow::Result,
+    program_authority_client::{
+        decode,
+        encode,
+        Encoding,
+    },
+    solana_sdk::{
+        signature::Keypair,
+        transaction::Transaction,
+    },
+    std::path::Path,
+};
+
+pub fn read_transaction_file(path: &Path, encoding: Encoding) -> Result<Transaction> {
+    Ok(decode(&std::fs::read_to_string(path)?, encoding)?)
+}
+
+pub fn write_transaction_file(
+    path: &Path,
+    transaction: &Transaction,
+    encoding: Encoding,
+) -> Result<()> {
+    std::fs::write(path, encode(transaction, encoding))?;
+    Ok(())
+}
+
+/// Adds the signatures of `keypairs` to a partially signed transaction file, in place
+pub fn sign_transaction_file(path: &Path, encoding: Encoding, keypairs: &[Keypair]) -> Result<()> {
+    let mut transaction = read_transaction_file(path, encoding)?;
+    let recent_blockhash = transaction.message.recent_blockhash;
+    transaction.try_partial_sign(&keypairs.iter().collect::<Vec<_>>(), recent_blockhash)?;
+    write_transaction_file(path, &transaction, encoding)
+}
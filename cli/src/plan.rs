@@ -0,0 +1,372 @@
+use {
+    crate::status::{
+        describe_status,
+        AddressBook,
+    },
+    anyhow::{
+        anyhow,
+        Result,
+    },
+    program_authority_client::{
+        deserialize_pubkey,
+        Cluster,
+    },
+    serde::Deserialize,
+    solana_sdk::{
+        hash::Hash,
+        instruction::Instruction,
+        pubkey::Pubkey,
+        signature::{
+            Keypair,
+            Signature,
+            Signer,
+        },
+        transaction::Transaction,
+    },
+    std::{
+        fmt,
+        path::Path,
+    },
+};
+
+/// Handovers to run together, read from a TOML file or from a JSON file with a `.json` extension
+#[derive(Deserialize, Debug)]
+pub struct Plan {
+    #[serde(rename = "handover")]
+    pub handovers: Vec<Handover>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Handover {
+    #[serde(deserialize_with = "deserialize_pubkey")]
+    pub program:           Pubkey,
+    #[serde(deserialize_with = "deserialize_pubkey")]
+    pub current_authority: Pubkey,
+    #[serde(deserialize_with = "deserialize_pubkey")]
+    pub new_authority:     Pubkey,
+    #[serde(flatten)]
+    pub mode:              HandoverMode,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(tag = "mode", rename_all = "lowercase")]
+pub enum HandoverMode {
+    Escrow,
+    Timelock { timestamp: i64 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    Propose,
+    Accept,
+    Commit,
+    Transfer,
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Step::Propose => write!(f, "propose"),
+            Step::Accept => write!(f, "accept"),
+            Step::Commit => write!(f, "commit"),
+            Step::Transfer => write!(f, "transfer"),
+        }
+    }
+}
+
+/// One instruction of a handover, sent in its own transaction
+#[derive(Clone, Debug)]
+pub struct Action {
+    pub step:        Step,
+    pub instruction: Instruction,
+    /// Key that has to sign besides the fee payer
+    pub signer:      Option<Pubkey>,
+    /// Timestamp before which the instruction fails
+    pub unlocks_at:  Option<i64>,
+}
+
+impl Action {
+    pub fn is_ready(&self, now: i64) -> bool {
+        self.unlocks_at.map_or(true, |timestamp| now >= timestamp)
+    }
+}
+
+impl Plan {
+    pub fn read(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => Ok(serde_json::from_str(&contents)?),
+            _ => Ok(toml::from_str(&contents)?),
+        }
+    }
+
+    /// Actions left for every handover, given the upgrade authorities of their programs in order
+    pub fn actions(&self, upgrade_authorities: &[Option<Pubkey>]) -> Result<Vec<Vec<Action>>> {
+        self.handovers
+            .iter()
+            .zip(upgrade_authorities.iter())
+            .map(|(handover, upgrade_authority)| handover.actions(*upgrade_authority))
+            .collect()
+    }
+
+    /// Upgrade authorities of the programs, in the order of the handovers
+    pub fn upgrade_authorities(&self, cluster: &impl Cluster) -> Result<Vec<Option<Pubkey>>> {
+        self.handovers
+            .iter()
+            .map(|handover| cluster.get_upgrade_authority(&handover.program))
+            .collect()
+    }
+
+    /// What `plan` prints, read from the cluster
+    pub fn describe(&self, cluster: &impl Cluster) -> Result<String> {
+        let upgrade_authorities = self.upgrade_authorities(cluster)?;
+        let actions = self.actions(&upgrade_authorities)?;
+        let now = cluster.get_clock()?.unix_timestamp;
+        Ok(describe_plan(self, &upgrade_authorities, &actions, now))
+    }
+
+    /// What `apply` does : fails before sending anything if a keypair is missing, then sends the
+    /// ready actions of every handover, one transaction each, and reads the upgrade authority back.
+    /// `on_sent` gets every transaction as it is confirmed
+    pub fn apply(
+        &self,
+        cluster: &impl Cluster,
+        fee_payer: &Keypair,
+        keypairs: &[Keypair],
+        mut on_sent: impl FnMut(&Handover, &Action, &Signature),
+    ) -> Result<()> {
+        let upgrade_authorities = self.upgrade_authorities(cluster)?;
+        let actions = self.actions(&upgrade_authorities)?;
+        for actions in actions.iter() {
+            check_signers(actions, &fee_payer.pubkey(), keypairs)?;
+        }
+
+        let now = cluster.get_clock()?.unix_timestamp;
+        for (handover, actions) in self.handovers.iter().zip(actions.iter()) {
+            for action in actions.iter().filter(|action| action.is_ready(now)) {
+                let transaction = action_transaction(
+                    action,
+                    fee_payer,
+                    keypairs,
+                    cluster.get_latest_blockhash()?,
+                )?;
+                let signature = cluster.send_transaction(&transaction)?;
+                on_sent(handover, action, &signature);
+            }
+            handover.verify(cluster.get_upgrade_authority(&handover.program)?, now)?;
+        }
+        Ok(())
+    }
+}
+
+impl Handover {
+    /// Key holding the upgrade authority between the two steps of the handover
+    pub fn intermediate_authority(&self) -> Pubkey {
+        match self.mode {
+            HandoverMode::Escrow => program_authority_client::escrow_authority(
+                &self.current_authority,
+                &self.new_authority,
+            ),
+            HandoverMode::Timelock { timestamp } => {
+                program_authority_client::timelock_authority(&self.new_authority, timestamp)
+            }
+        }
+    }
+
+    /// Instructions left to give the upgrade authority to the new authority, starting from
+    /// `upgrade_authority`
+    pub fn actions(&self, upgrade_authority: Option<Pubkey>) -> Result<Vec<Action>> {
+        let upgrade_authority =
+            upgrade_authority.ok_or_else(|| anyhow!("Program {} is immutable", self.program))?;
+
+        let mut actions = vec![];
+        if upgrade_authority == self.new_authority {
+            return Ok(actions);
+        }
+        if upgrade_authority == self.current_authority {
+            actions.push(self.first_action());
+        } else if upgrade_authority != self.intermediate_authority() {
+            return Err(anyhow!(
+                "Program {} is held by {upgrade_authority}, expected {}",
+                self.program,
+                self.current_authority
+            ));
+        }
+        actions.push(self.second_action());
+        Ok(actions)
+    }
+
+    fn first_action(&self) -> Action {
+        match self.mode {
+            HandoverMode::Escrow => Action {
+                step:        Step::Propose,
+                instruction: program_authority_client::propose_ix(
+                    &self.current_authority,
+                    &self.new_authority,
+                    &self.program,
+                    None,
+                ),
+                signer:      Some(self.current_authority),
+                unlocks_at:  None,
+            },
+            HandoverMode::Timelock { timestamp } => Action {
+                step:        Step::Commit,
+                instruction: program_authority_client::commit_ix(
+                    &self.current_authority,
+                    &self.new_authority,
+                    &self.program,
+                    timestamp,
+                    None,
+                ),
+                signer:      Some(self.current_authority),
+                unlocks_at:  None,
+            },
+        }
+    }
+
+    fn second_action(&self) -> Action {
+        match self.mode {
+            HandoverMode::Escrow => Action {
+                step:        Step::Accept,
+                instruction: program_authority_client::accept_ix(
+                    &self.current_authority,
+                    &self.new_authority,
+                    &self.program,
+                    None,
+                ),
+                signer:      Some(self.new_authority),
+                unlocks_at:  None,
+            },
+            HandoverMode::Timelock { timestamp } => Action {
+                step:        Step::Transfer,
+                instruction: program_authority_client::transfer_ix(
+                    &self.new_authority,
+                    &self.program,
+                    timestamp,
+                    None,
+                ),
+                signer:      None,
+                unlocks_at:  Some(timestamp),
+            },
+        }
+    }
+
+    /// Upgrade authority expected once the ready actions have been sent : the new authority, or the
+    /// timelock while it hasn't matured
+    pub fn expected_authority(&self, now: i64) -> Pubkey {
+        match self.mode {
+            HandoverMode::Timelock { timestamp } if now < timestamp => {
+                self.intermediate_authority()
+            }
+            _ => self.new_authority,
+        }
+    }
+
+    /// Fails if the upgrade authority read back after `apply` isn't the expected one
+    pub fn verify(&self, upgrade_authority: Option<Pubkey>, now: i64) -> Result<()> {
+        let expected_authority = self.expected_authority(now);
+        if upgrade_authority != Some(expected_authority) {
+            return Err(anyhow!(
+                "Program {} ended up with authority {:?}, expected {expected_authority}",
+                self.program,
+                upgrade_authority
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// What `plan` prints : the state of every program, the actions left and the keys that have to
+/// sign them
+pub fn describe_plan(
+    plan: &Plan,
+    upgrade_authorities: &[Option<Pubkey>],
+    actions: &[Vec<Action>],
+    now: i64,
+) -> String {
+    let mut lines = vec![];
+    let mut signers = vec![];
+
+    for ((handover, upgrade_authority), actions) in plan
+        .handovers
+        .iter()
+        .zip(upgrade_authorities.iter())
+        .zip(actions.iter())
+    {
+        let mut address_book = AddressBook::default();
+        address_book.add(handover.current_authority);
+        address_book.add(handover.new_authority);
+        let timestamps = match handover.mode {
+            HandoverMode::Escrow => vec![],
+            HandoverMode::Timelock { timestamp } => vec![timestamp],
+        };
+        let status = program_authority_client::inspect_authority(
+            &handover.program,
+            *upgrade_authority,
+            &address_book.keys(),
+            &timestamps,
+            &[],
+            now,
+        );
+        lines.push(format!(
+            "{}: {}",
+            handover.program,
+            describe_status(&status, &address_book)
+        ));
+
+        if actions.is_empty() {
+            lines.push("  nothing to do".to_string());
+        }
+        for action in actions.iter() {
+            let mut line = format!("  {}", action.step);
+            if let Some(signer) = action.signer {
+                line.push_str(&format!(", signed by {signer}"));
+                if !signers.contains(&signer) {
+                    signers.push(signer);
+                }
+            }
+            if let Some(timestamp) = action.unlocks_at.filter(|_| !action.is_ready(now)) {
+                line.push_str(&format!(", not before {timestamp}"));
+            }
+            lines.push(line);
+        }
+    }
+
+    lines.push("Signers:".to_string());
+    lines.extend(signers.iter().map(|signer| format!("  {signer}")));
+    lines.join("\n")
+}
+
+/// Fails before anything is sent if one of the actions needs a keypair that wasn't given
+pub fn check_signers(actions: &[Action], fee_payer: &Pubkey, keypairs: &[Keypair]) -> Result<()> {
+    for signer in actions.iter().filter_map(|action| action.signer) {
+        if signer != *fee_payer && !keypairs.iter().any(|keypair| keypair.pubkey() == signer) {
+            return Err(anyhow!("Missing the keypair of {signer}"));
+        }
+    }
+    Ok(())
+}
+
+/// Signs the transaction of an action with the fee payer and the keypair of its signer
+pub fn action_transaction(
+    action: &Action,
+    fee_payer: &Keypair,
+    keypairs: &[Keypair],
+    recent_blockhash: Hash,
+) -> Result<Transaction> {
+    let mut signers = vec![fee_payer];
+    if let Some(signer) = action.signer.filter(|signer| *signer != fee_payer.pubkey()) {
+        signers.push(
+            keypairs
+                .iter()
+                .find(|keypair| keypair.pubkey() == signer)
+                .ok_or_else(|| anyhow!("Missing the keypair of {signer}"))?,
+        );
+    }
+    Ok(Transaction::new_signed_with_payer(
+        &[action.instruction.clone()],
+        Some(&fee_payer.pubkey()),
+        &signers,
+        recent_blockhash,
+    ))
+}
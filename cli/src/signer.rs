@@ -0,0 +1,47 @@
+use {
+    anyhow::Result,
+    program_authority_client::read_keypair,
+    solana_sdk::{
+        pubkey::Pubkey,
+        signature::{
+            Keypair,
+            Signer,
+        },
+    },
+    std::{
+        convert::Infallible,
+        path::PathBuf,
+        str::FromStr,
+    },
+};
+
+/// A keypair file, or only a public key when the signature is added offline with `sign`
+#[derive(Clone, Debug)]
+pub enum SignerSource {
+    Keypair(PathBuf),
+    Pubkey(Pubkey),
+}
+
+impl FromStr for SignerSource {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match Pubkey::from_str(s) {
+            Ok(pubkey) => SignerSource::Pubkey(pubkey),
+            Err(_) => SignerSource::Keypair(PathBuf::from(s)),
+        })
+    }
+}
+
+impl SignerSource {
+    /// Returns the public key of the signer and its keypair when it is available
+    pub fn resolve(&self) -> Result<(Pubkey, Option<Keypair>)> {
+        match self {
+            SignerSource::Keypair(path) => {
+                let keypair = read_keypair(path)?;
+                Ok((keypair.pubkey(), Some(keypair)))
+            }
+            SignerSource::Pubkey(pubkey) => Ok((*pubkey, None)),
+        }
+    }
+}
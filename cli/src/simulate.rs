@@ -0,0 +1,251 @@
+use {
+    crate::{
+        describe_status,
+        AddressBook,
+        Cli,
+    },
+    anchor_lang::AnchorDeserialize,
+    anyhow::{
+        anyhow,
+        Result,
+    },
+    program_authority_client::{
+        Cluster,
+        DecodedHandover,
+    },
+    program_authority_common::AuthorityStatus,
+    solana_program_test::ProgramTest,
+    solana_sdk::{
+        account::Account,
+        clock::Clock,
+        hash::{
+            hash,
+            Hash,
+        },
+        instruction::Instruction,
+        message::Message,
+        program::MAX_RETURN_DATA,
+        pubkey::Pubkey,
+        signature::Signer,
+        transaction::{
+            Transaction,
+            TransactionError,
+        },
+    },
+    std::path::PathBuf,
+};
+
+/// Upgrade authority of a program before and after a handover instruction ran in a local bank
+#[derive(Debug)]
+pub struct Simulation {
+    pub authority_before: Option<Pubkey>,
+    /// Same as `authority_before` when the instruction failed
+    pub authority_after:  Option<Pubkey>,
+    pub error:            Option<TransactionError>,
+    pub logs:             Vec<String>,
+}
+
+/// Decodes an escrow or timelock handover instruction built by the CLI
+pub fn decode_instruction(instruction: &Instruction) -> Result<DecodedHandover> {
+    let accounts: Vec<Pubkey> = instruction
+        .accounts
+        .iter()
+        .map(|meta| meta.pubkey)
+        .collect();
+    program_authority_client::decode_handover(&instruction.program_id, &accounts, &instruction.data)
+        .ok_or_else(|| anyhow!("Not an escrow or timelock handover instruction"))
+}
+
+/// What `--simulate` prints for the handover of `cli`, with the program accounts and the clock
+/// of `cluster`
+pub fn run_simulation(cli: &Cli, cluster: &impl Cluster, recent_blockhash: Hash) -> Result<String> {
+    let (instruction, _) = cli.command.prepare()?;
+    let handover = decode_instruction(&instruction)?;
+    let program_data = program_authority_client::program_data_address(&handover.program_account);
+    let program_account = cluster
+        .get_account(&handover.program_account)?
+        .ok_or_else(|| anyhow!("Program {} not found", handover.program_account))?;
+    let program_data_account = cluster
+        .get_account(&program_data)?
+        .ok_or_else(|| anyhow!("Program data account {program_data} not found"))?;
+    let clock = cluster.get_clock()?;
+
+    // The cluster may block on a runtime of its own, the bank gets a separate one
+    let simulation = tokio::runtime::Runtime::new()?.block_on(simulate_handover(
+        &instruction,
+        program_account,
+        program_data_account,
+        &clock,
+    ))?;
+    Ok(describe_simulation(
+        &cli.message(recent_blockhash)?,
+        &handover,
+        &simulation,
+        clock.unix_timestamp,
+    ))
+}
+
+/// Runs the instruction in a local bank holding the escrow and timelock programs and copies of the
+/// program and program data accounts, with the clock of the cluster. Simulating doesn't check
+/// signatures, so the authorities don't have to sign first
+pub async fn simulate_handover(
+    instruction: &Instruction,
+    program_account: Account,
+    program_data_account: Account,
+    clock: &Clock,
+) -> Result<Simulation> {
+    let program = decode_instruction(instruction)?.program_account;
+    let authority_before =
+        program_authority_client::decode_upgrade_authority(&program_data_account.data)?;
+
+    // `ProgramTest` panics when it can't find the programs
+    let bpf_out_dir = std::env::var_os("BPF_OUT_DIR")
+        .map(PathBuf::from)
+        .ok_or_else(|| {
+            anyhow!("Set BPF_OUT_DIR to the directory holding the escrow and timelock programs")
+        })?;
+    for program_name in ["program_authority_escrow", "program_authority_timelock"] {
+        let path = bpf_out_dir.join(format!("{program_name}.so"));
+        if !path.is_file() {
+            return Err(anyhow!(
+                "{} not found, BPF_OUT_DIR has to hold the escrow and timelock programs",
+                path.display()
+            ));
+        }
+    }
+
+    let mut program_test = ProgramTest::new(
+        "program_authority_escrow",
+        program_authority_escrow::id(),
+        None,
+    );
+    program_test.add_program(
+        "program_authority_timelock",
+        program_authority_timelock::id(),
+        None,
+    );
+    program_test.add_account(program, program_account);
+    program_test.add_account(
+        program_authority_client::program_data_address(&program),
+        program_data_account,
+    );
+    let mut context = program_test.start_with_context().await;
+    context.set_sysvar(clock);
+
+    // Without candidates, the return data of `status` is the upgrade authority left by the handover
+    let message = Message::new_with_blockhash(
+        &[
+            instruction.clone(),
+            program_authority_client::status_ix(&program, vec![], vec![], vec![]),
+        ],
+        Some(&context.payer.pubkey()),
+        &context.last_blockhash,
+    );
+    let result = context
+        .banks_client
+        .simulate_transaction(Transaction::new_unsigned(message))
+        .await?;
+    // Without details the transaction was rejected before running, it isn't a program error
+    let (error, details) = match (result.result, result.simulation_details) {
+        (result, Some(details)) => (result.and_then(|result| result.err()), details),
+        (Some(Err(err)), None) => return Err(anyhow!("Failed to simulate the transaction: {err}")),
+        (_, None) => return Err(anyhow!("Failed to simulate the transaction")),
+    };
+
+    let authority_after = match (&error, details.return_data) {
+        (Some(_), _) => authority_before,
+        (None, Some(return_data)) => decode_status(return_data.data)?,
+        (None, None) => return Err(anyhow!("The status instruction didn't return anything")),
+    };
+
+    Ok(Simulation {
+        authority_before,
+        authority_after,
+        error,
+        logs: details.logs,
+    })
+}
+
+/// The bank trims the trailing zeros of return data, they are added back for Borsh
+fn decode_status(mut data: Vec<u8>) -> Result<Option<Pubkey>> {
+    data.resize(MAX_RETURN_DATA, 0);
+    match AuthorityStatus::deserialize(&mut data.as_slice())? {
+        AuthorityStatus::Direct(upgrade_authority) => Ok(Some(upgrade_authority)),
+        AuthorityStatus::Immutable => Ok(None),
+        status => Err(anyhow!("Unexpected status without candidates {status:?}")),
+    }
+}
+
+/// Summary to compare with the hardware wallet before signing : the message hash it shows when
+/// blind signing, the fee payer, the handover and the upgrade authority before and after, followed
+/// by the logs of the simulation
+pub fn describe_simulation(
+    message: &Message,
+    handover: &DecodedHandover,
+    simulation: &Simulation,
+    now: i64,
+) -> String {
+    let mut address_book = AddressBook::default();
+    if let Some(current_authority) = handover.current_authority {
+        address_book.add(current_authority);
+    }
+    address_book.add(handover.new_authority);
+    if let Some(next_authority) = handover.next_authority {
+        address_book.add(next_authority);
+    }
+    let timestamps: Vec<i64> = handover.timestamp.into_iter().collect();
+    let nonces: Vec<u64> = handover.nonce.into_iter().collect();
+    let describe_authority = |authority: Option<Pubkey>| {
+        let status = program_authority_client::inspect_authority(
+            &handover.program_account,
+            authority,
+            &address_book.keys(),
+            &timestamps,
+            &nonces,
+            now,
+        );
+        format!(
+            "{}\n  {}",
+            authority.map_or("none".to_string(), |authority| authority.to_string()),
+            describe_status(&status, &address_book)
+        )
+    };
+
+    let mut lines = vec![
+        format!("Message hash: {}", hash(&message.serialize())),
+        format!("Fee payer: {}", message.account_keys[0]),
+        format!("Instruction: {}", handover.instruction),
+        format!("Program: {}", handover.program_account),
+    ];
+    if let Some(current_authority) = handover.current_authority {
+        lines.push(format!("Current authority: {current_authority}"));
+    }
+    lines.push(format!("New authority: {}", handover.new_authority));
+    if let Some(next_authority) = handover.next_authority {
+        lines.push(format!("Next authority: {next_authority}"));
+    }
+    if let Some(timestamp) = handover.timestamp {
+        lines.push(format!("Timestamp: {timestamp}"));
+    }
+    if let Some(nonce) = handover.nonce {
+        lines.push(format!("Nonce: {nonce}"));
+    }
+    if let Some(memo) = &handover.memo {
+        lines.push(format!("Memo: {memo}"));
+    }
+    lines.push(format!(
+        "Upgrade authority before: {}",
+        describe_authority(simulation.authority_before)
+    ));
+    lines.push(format!(
+        "Upgrade authority after: {}",
+        describe_authority(simulation.authority_after)
+    ));
+    lines.push(match &simulation.error {
+        None => "Result: success".to_string(),
+        Some(err) => format!("Result: failed, {err}"),
+    });
+    lines.push("Logs:".to_string());
+    lines.extend(simulation.logs.iter().map(|log| format!("  {log}")));
+    lines.join("\n")
+}
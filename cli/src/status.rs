@@ -0,0 +1,172 @@
+use {
+    anyhow::{
+        anyhow,
+        Result,
+    },
+    program_authority_common::AuthorityStatus,
+    serde::Deserialize,
+    solana_sdk::{
+        bpf_loader_upgradeable,
+        pubkey::Pubkey,
+    },
+    std::{
+        path::Path,
+        str::FromStr,
+    },
+};
+
+/// Keys to match the escrow and timelock addresses against, with the labels to print them with
+#[derive(Default, Debug)]
+pub struct AddressBook {
+    entries: Vec<(Pubkey, Option<String>)>,
+}
+
+impl AddressBook {
+    /// Parses one public key per line, optionally followed by a label. Blank lines and lines
+    /// starting with `#` are skipped
+    pub fn parse(contents: &str) -> Result<Self> {
+        let mut address_book = AddressBook::default();
+        for line in contents.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, label) = match line.split_once(char::is_whitespace) {
+                Some((key, label)) => (key, Some(label.trim().to_string())),
+                None => (line, None),
+            };
+            let key = Pubkey::from_str(key).map_err(|_| anyhow!("Invalid public key {key}"))?;
+            address_book.entries.push((key, label));
+        }
+        Ok(address_book)
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Adds a key without a label, unless it is already in the book
+    pub fn add(&mut self, key: Pubkey) {
+        if !self.entries.iter().any(|(entry, _)| *entry == key) {
+            self.entries.push((key, None));
+        }
+    }
+
+    pub fn keys(&self) -> Vec<Pubkey> {
+        self.entries.iter().map(|(key, _)| *key).collect()
+    }
+
+    /// The key followed by its label when it has one
+    pub fn describe(&self, key: &Pubkey) -> String {
+        match self.entries.iter().find(|(entry, _)| entry == key) {
+            Some((_, Some(label))) => format!("{key} ({label})"),
+            _ => key.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct AccountDump {
+    pubkey:  String,
+    account: DumpedAccount,
+}
+
+#[derive(Deserialize)]
+struct DumpedAccount {
+    owner: String,
+    data:  (String, String),
+}
+
+/// Reads an account saved with `solana account <ADDRESS> --output json`, returns its address, its
+/// owner and its data
+pub fn read_account_dump(path: &Path) -> Result<(Pubkey, Pubkey, Vec<u8>)> {
+    let dump: AccountDump = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    let (data, encoding) = dump.account.data;
+    let data = match encoding.as_str() {
+        "base64" => base64::decode(data)?,
+        _ => return Err(anyhow!("Unsupported account data encoding {encoding}")),
+    };
+    Ok((
+        Pubkey::from_str(&dump.pubkey)?,
+        Pubkey::from_str(&dump.account.owner)?,
+        data,
+    ))
+}
+
+/// Checks that the account is the program data account of `program` and classifies its upgrade
+/// authority
+pub fn inspect_program_data(
+    program: &Pubkey,
+    address: &Pubkey,
+    owner: &Pubkey,
+    data: &[u8],
+    address_book: &AddressBook,
+    timestamps: &[i64],
+    nonces: &[u64],
+    now: i64,
+) -> Result<AuthorityStatus> {
+    if *address != program_authority_client::program_data_address(program) {
+        return Err(anyhow!(
+            "{address} is not the program data account of {program}"
+        ));
+    }
+    if *owner != bpf_loader_upgradeable::id() {
+        return Err(anyhow!("{address} is not owned by the upgradeable loader"));
+    }
+    let upgrade_authority = program_authority_client::decode_upgrade_authority(data)?;
+    Ok(program_authority_client::inspect_authority(
+        program,
+        upgrade_authority,
+        &address_book.keys(),
+        timestamps,
+        nonces,
+        now,
+    ))
+}
+
+pub fn describe_status(status: &AuthorityStatus, address_book: &AddressBook) -> String {
+    match status {
+        AuthorityStatus::Direct(upgrade_authority) => format!(
+            "Held directly by {}",
+            address_book.describe(upgrade_authority)
+        ),
+        AuthorityStatus::Escrowed {
+            current_authority,
+            new_authority,
+        } => format!(
+            "In escrow from {} to {}",
+            address_book.describe(current_authority),
+            address_book.describe(new_authority)
+        ),
+        AuthorityStatus::ScopedEscrowed {
+            current_authority,
+            new_authority,
+            nonce,
+        } => format!(
+            "In escrow from {} to {} with nonce {nonce}",
+            address_book.describe(current_authority),
+            address_book.describe(new_authority)
+        ),
+        AuthorityStatus::PendingImmutable {
+            current_authority,
+            confirm_authority,
+        } => format!(
+            "Proposed immutable by {}, waiting for {} to confirm",
+            address_book.describe(current_authority),
+            address_book.describe(confirm_authority)
+        ),
+        AuthorityStatus::Timelocked {
+            new_authority,
+            timestamp,
+            matured,
+        } => format!(
+            "Timelocked for {} until {timestamp}, {}",
+            address_book.describe(new_authority),
+            if *matured {
+                "ready to transfer"
+            } else {
+                "not matured yet"
+            }
+        ),
+        AuthorityStatus::Immutable => "Immutable".to_string(),
+    }
+}
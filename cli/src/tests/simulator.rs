@@ -0,0 +1,368 @@
+#[cfg(feature = "simulate")]
+use crate::run_simulation;
+use {
+    crate::{
+        read_account_dump,
+        read_transaction_file,
+        sign_transaction_file,
+        Cli,
+        Command,
+        Plan,
+    },
+    anchor_lang::{
+        prelude::{
+            Clock,
+            Pubkey,
+            Rent,
+            UpgradeableLoaderState,
+        },
+        AccountDeserialize,
+        ProgramData,
+    },
+    clap::Parser,
+    program_authority_client::{
+        Cluster,
+        Encoding,
+    },
+    solana_program_test::{
+        read_file,
+        BanksClientError,
+        ProgramTest,
+        ProgramTestContext,
+    },
+    solana_sdk::{
+        account::Account,
+        bpf_loader_upgradeable,
+        hash::Hash,
+        signature::{
+            write_keypair_file,
+            Keypair,
+            Signature,
+            Signer,
+        },
+        stake_history::Epoch,
+        transaction::Transaction,
+    },
+    std::{
+        future::Future,
+        path::{
+            Path,
+            PathBuf,
+        },
+    },
+    tokio::runtime::Handle,
+};
+
+
+pub struct CliSimulator {
+    context:            ProgramTestContext,
+    helloworld_address: Pubkey,
+    keypair_dir:        PathBuf,
+}
+
+impl CliSimulator {
+    /// Deploys the escrow, the timelock and an upgradable program to hand over
+    pub async fn new() -> (CliSimulator, Keypair) {
+        let mut bpf_data = read_file(PathBuf::from("../tests/fixtures/helloworld.so"));
+
+        let mut program_test = ProgramTest::new(
+            "program_authority_escrow",
+            program_authority_escrow::id(),
+            None,
+        );
+        program_test.add_program(
+            "program_authority_timelock",
+            program_authority_timelock::id(),
+            None,
+        );
+        let upgrade_authority = Keypair::new();
+
+        let helloworld_address = add_program_as_upgradable(
+            &mut bpf_data,
+            &upgrade_authority.pubkey(),
+            &mut program_test,
+        );
+
+        let context = program_test.start_with_context().await;
+
+        let keypair_dir =
+            std::env::temp_dir().join(format!("program-authority-cli-{}", Pubkey::new_unique()));
+        std::fs::create_dir_all(&keypair_dir).unwrap();
+
+        (
+            CliSimulator {
+                context,
+                helloworld_address,
+                keypair_dir,
+            },
+            upgrade_authority,
+        )
+    }
+}
+
+pub fn add_program_as_upgradable(
+    data: &mut Vec<u8>,
+    upgrade_authority: &Pubkey,
+    program_test: &mut ProgramTest,
+) -> Pubkey {
+    let program_key = Pubkey::new_unique();
+    let (programdata_key, _) =
+        Pubkey::find_program_address(&[&program_key.to_bytes()], &bpf_loader_upgradeable::id());
+
+
+    let program_deserialized = UpgradeableLoaderState::Program {
+        programdata_address: programdata_key,
+    };
+    let programdata_deserialized = UpgradeableLoaderState::ProgramData {
+        slot:                      1,
+        upgrade_authority_address: Some(*upgrade_authority),
+    };
+
+    // Program contains a pointer to progradata
+    let program_vec = bincode::serialize(&program_deserialized).unwrap();
+    // Programdata contains a header and the binary of the program
+    let mut programdata_vec = bincode::serialize(&programdata_deserialized).unwrap();
+    programdata_vec.append(data);
+
+    let program_account = Account {
+        lamports:   Rent::default().minimum_balance(program_vec.len()),
+        data:       program_vec,
+        owner:      bpf_loader_upgradeable::ID,
+        executable: true,
+        rent_epoch: Epoch::default(),
+    };
+    let programdata_account = Account {
+        lamports:   Rent::default().minimum_balance(programdata_vec.len()),
+        data:       programdata_vec,
+        owner:      bpf_loader_upgradeable::ID,
+        executable: false,
+        rent_epoch: Epoch::default(),
+    };
+
+    // Add both accounts to program test, now the program is deployed as upgradable
+    program_test.add_account(program_key, program_account);
+    program_test.add_account(programdata_key, programdata_account);
+
+    program_key
+}
+
+
+impl CliSimulator {
+    /// Parses the arguments like the binary does and processes the resulting transaction,
+    /// `context.payer` pays the fees
+    pub async fn run(&mut self, args: &[&str]) -> Result<(), BanksClientError> {
+        let cli = self.parse(args);
+        let blockhash = self.get_latest_blockhash().await;
+        let transaction = cli.build_transaction(blockhash).unwrap();
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+    }
+
+    /// Same as `run` with `--export`, returns what the binary would print
+    pub async fn export(&mut self, args: &[&str]) -> String {
+        let cli = self.parse(args);
+        let blockhash = match cli.blockhash {
+            Some(blockhash) => blockhash,
+            None => self.get_latest_blockhash().await,
+        };
+        cli.export(cli.export.unwrap(), blockhash).unwrap()
+    }
+
+    /// Same as `run` with `--simulate`, through the binary's code with this bank as the cluster
+    #[cfg(feature = "simulate")]
+    pub fn simulate(&self, args: &[&str]) -> anyhow::Result<String> {
+        let cli = self.parse(args);
+        let blockhash = match cli.blockhash {
+            Some(blockhash) => blockhash,
+            None => Cluster::get_latest_blockhash(self)?,
+        };
+        // Leaves the test runtime so that the simulation can start its own
+        tokio::task::block_in_place(|| run_simulation(&cli, self, blockhash))
+    }
+
+    /// Same as `run` for `status`, the program data account comes from the bank unless a dump is
+    /// given and timelocks mature according to the bank clock
+    pub async fn status(&mut self, args: &[&str]) -> anyhow::Result<String> {
+        let cli = self.parse(args);
+        let (address, owner, data) = match &cli.command {
+            Command::Status {
+                program_data_dump: Some(path),
+                ..
+            } => read_account_dump(path)?,
+            _ => {
+                let address = self.get_program_data_address();
+                let account = self.get_account(address).await;
+                (address, account.owner, account.data)
+            }
+        };
+        let now = self.get_clock().await.unix_timestamp;
+        cli.command.status(&address, &owner, &data, now)
+    }
+
+    /// Saves the program data account like `solana account --output json` does, under `address`
+    pub async fn dump_program_data(&mut self, address: &Pubkey) -> PathBuf {
+        let account = self.get_account(self.get_program_data_address()).await;
+        let path = self.keypair_dir.join(format!("{address}.dump.json"));
+        let dump = serde_json::json!({
+            "pubkey": address.to_string(),
+            "account": {
+                "lamports": account.lamports,
+                "data": [base64::encode(&account.data), "base64"],
+                "owner": account.owner.to_string(),
+                "executable": account.executable,
+                "rentEpoch": account.rent_epoch,
+            },
+        });
+        std::fs::write(&path, dump.to_string()).unwrap();
+        path
+    }
+
+    /// Same as the binary's `plan`, with the bank clock
+    pub fn plan(&self, plan_path: &Path) -> anyhow::Result<String> {
+        Plan::read(plan_path)?.describe(self)
+    }
+
+    /// Same as the binary's `apply`, `context.payer` pays the fees. Returns the program and step
+    /// of every transaction sent
+    pub fn apply(&self, plan_path: &Path, keypairs: &[&Keypair]) -> anyhow::Result<Vec<String>> {
+        let fee_payer = Keypair::from_bytes(&self.context.payer.to_bytes()).unwrap();
+        let keypairs: Vec<Keypair> = keypairs
+            .iter()
+            .map(|keypair| Keypair::from_bytes(&keypair.to_bytes()).unwrap())
+            .collect();
+        let mut sent = vec![];
+        Plan::read(plan_path)?.apply(self, &fee_payer, &keypairs, |handover, action, _| {
+            sent.push(format!("{} {}", handover.program, action.step))
+        })?;
+        Ok(sent)
+    }
+
+    pub fn sign(&self, transaction_path: &Path, keypair: &Keypair) {
+        let keypair = Keypair::from_bytes(&keypair.to_bytes()).unwrap();
+        sign_transaction_file(transaction_path, Encoding::Base64, &[keypair]).unwrap();
+    }
+
+    pub async fn submit(&mut self, transaction_path: &Path) -> Result<(), BanksClientError> {
+        let transaction = read_transaction_file(transaction_path, Encoding::Base64).unwrap();
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+    }
+
+    fn parse(&self, args: &[&str]) -> Cli {
+        let fee_payer = Keypair::from_bytes(&self.context.payer.to_bytes()).unwrap();
+        let fee_payer_path = self.write_keypair(&fee_payer);
+
+        Cli::try_parse_from(
+            ["program-authority", "--fee-payer", &fee_payer_path]
+                .iter()
+                .chain(args.iter()),
+        )
+        .unwrap()
+    }
+
+    async fn get_latest_blockhash(&mut self) -> Hash {
+        self.context
+            .banks_client
+            .get_latest_blockhash()
+            .await
+            .unwrap()
+    }
+
+    async fn get_clock(&mut self) -> Clock {
+        self.context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+    }
+
+    pub fn get_payer(&self) -> Pubkey {
+        self.context.payer.pubkey()
+    }
+
+    pub fn get_keypair_dir(&self) -> &Path {
+        &self.keypair_dir
+    }
+
+    /// Writes the keypair where the CLI can read it and returns the path
+    pub fn write_keypair(&self, keypair: &Keypair) -> String {
+        let path = self.keypair_dir.join(format!("{}.json", keypair.pubkey()));
+        write_keypair_file(keypair, &path).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    pub fn get_program(&self) -> String {
+        self.helloworld_address.to_string()
+    }
+
+    pub fn get_program_data_address(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[self.helloworld_address.as_ref()],
+            &bpf_loader_upgradeable::id(),
+        )
+        .0
+    }
+
+    async fn get_account(&mut self, address: Pubkey) -> Account {
+        self.context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    pub async fn get_program_data(&mut self) -> ProgramData {
+        let account = self.get_account(self.get_program_data_address()).await;
+        ProgramData::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub async fn warp_to_timestamp(&mut self, timestamp: i64) {
+        let current_clock = self
+            .context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap();
+        self.context.set_sysvar::<Clock>(&Clock {
+            unix_timestamp: timestamp,
+            ..current_clock
+        });
+    }
+
+    pub async fn check_program_authority_matches(&mut self, upgrade_authority: &Pubkey) {
+        let program_data = self.get_program_data().await;
+        assert_eq!(
+            program_data.upgrade_authority_address,
+            Some(*upgrade_authority)
+        );
+    }
+}
+
+/// The CLI reads the cluster synchronously, the calls block on the test runtime, which has to be
+/// the multi-threaded one
+impl Cluster for CliSimulator {
+    fn get_account(&self, address: &Pubkey) -> anyhow::Result<Option<Account>> {
+        let mut banks_client = self.context.banks_client.clone();
+        Ok(block_on(banks_client.get_account(*address))?)
+    }
+
+    fn get_latest_blockhash(&self) -> anyhow::Result<Hash> {
+        let mut banks_client = self.context.banks_client.clone();
+        Ok(block_on(banks_client.get_latest_blockhash())?)
+    }
+
+    fn send_transaction(&self, transaction: &Transaction) -> anyhow::Result<Signature> {
+        let mut banks_client = self.context.banks_client.clone();
+        block_on(banks_client.process_transaction(transaction.clone()))?;
+        Ok(transaction.signatures[0])
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    tokio::task::block_in_place(|| Handle::current().block_on(future))
+}
@@ -0,0 +1,711 @@
+use {
+    crate::{
+        history_report,
+        read_transaction_file,
+        read_transactions_file,
+        tests::simulator::CliSimulator,
+        ReportFormat,
+    },
+    program_authority_client::{
+        decode,
+        decode_transaction,
+        Encoding,
+    },
+    solana_sdk::{
+        hash::Hash,
+        instruction::Instruction,
+        message::Message,
+        pubkey::Pubkey,
+        signature::{
+            Keypair,
+            Signer,
+        },
+        system_instruction,
+    },
+    std::{
+        path::Path,
+        str::FromStr,
+    },
+};
+
+#[tokio::test]
+async fn test_escrow() {
+    let (mut simulator, authority_keypair_1) = CliSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+
+    let program = simulator.get_program();
+    let authority_1_path = simulator.write_keypair(&authority_keypair_1);
+    let authority_2_path = simulator.write_keypair(&authority_keypair_2);
+    let authority_1 = authority_keypair_1.pubkey().to_string();
+    let authority_2 = authority_keypair_2.pubkey().to_string();
+    let escrow_authority = program_authority_client::escrow_authority(
+        &authority_keypair_1.pubkey(),
+        &authority_keypair_2.pubkey(),
+    );
+
+    simulator
+        .run(&[
+            "escrow",
+            "propose",
+            "--program",
+            &program,
+            "--current-authority",
+            &authority_1_path,
+            "--new-authority",
+            &authority_2,
+        ])
+        .await
+        .unwrap();
+    simulator
+        .check_program_authority_matches(&escrow_authority)
+        .await;
+
+    simulator
+        .run(&[
+            "escrow",
+            "revert",
+            "--program",
+            &program,
+            "--current-authority",
+            &authority_1_path,
+            "--new-authority",
+            &authority_2,
+            "--reason",
+            "Wrong new authority",
+        ])
+        .await
+        .unwrap();
+    simulator
+        .check_program_authority_matches(&authority_keypair_1.pubkey())
+        .await;
+
+    simulator
+        .run(&[
+            "escrow",
+            "propose",
+            "--program",
+            &program,
+            "--current-authority",
+            &authority_1_path,
+            "--new-authority",
+            &authority_2,
+        ])
+        .await
+        .unwrap();
+    simulator
+        .run(&[
+            "escrow",
+            "accept",
+            "--program",
+            &program,
+            "--current-authority",
+            &authority_1,
+            "--new-authority",
+            &authority_2_path,
+        ])
+        .await
+        .unwrap();
+    simulator
+        .check_program_authority_matches(&authority_keypair_2.pubkey())
+        .await;
+}
+
+#[tokio::test]
+async fn test_timelock() {
+    let (mut simulator, authority_keypair_1) = CliSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+
+    let program = simulator.get_program();
+    let authority_1_path = simulator.write_keypair(&authority_keypair_1);
+    let authority_2 = authority_keypair_2.pubkey().to_string();
+
+    simulator
+        .run(&[
+            "timelock",
+            "commit",
+            "--program",
+            &program,
+            "--current-authority",
+            &authority_1_path,
+            "--new-authority",
+            &authority_2,
+            "--timestamp",
+            "0",
+        ])
+        .await
+        .unwrap();
+    simulator
+        .check_program_authority_matches(&program_authority_client::timelock_authority(
+            &authority_keypair_2.pubkey(),
+            0,
+        ))
+        .await;
+
+    simulator
+        .run(&[
+            "timelock",
+            "transfer",
+            "--program",
+            &program,
+            "--new-authority",
+            &authority_2,
+            "--timestamp",
+            "0",
+        ])
+        .await
+        .unwrap();
+    simulator
+        .check_program_authority_matches(&authority_keypair_2.pubkey())
+        .await;
+}
+
+#[tokio::test]
+async fn test_export() {
+    let (mut simulator, authority_keypair_1) = CliSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+
+    let program = simulator.get_program();
+    let authority_1 = authority_keypair_1.pubkey().to_string();
+    let authority_2 = authority_keypair_2.pubkey().to_string();
+
+    for encoding in ["base58", "base64"] {
+        let exported = simulator
+            .export(&[
+                "escrow",
+                "propose",
+                "--program",
+                &program,
+                "--current-authority",
+                &authority_1,
+                "--new-authority",
+                &authority_2,
+                "--export",
+                "instructions",
+                "--encoding",
+                encoding,
+            ])
+            .await;
+        let instruction: Instruction =
+            decode(&exported, Encoding::from_str(encoding).unwrap()).unwrap();
+        assert_eq!(
+            instruction,
+            program_authority_client::propose_ix(
+                &authority_keypair_1.pubkey(),
+                &authority_keypair_2.pubkey(),
+                &Pubkey::from_str(&program).unwrap(),
+                None,
+            )
+        );
+    }
+
+    // With a durable nonce the message starts with the nonce advance and uses the nonce as its hash
+    let nonce = Pubkey::new_unique();
+    let nonce_authority = Pubkey::new_unique();
+    let durable_nonce = Hash::new_unique();
+    let exported = simulator
+        .export(&[
+            "timelock",
+            "commit",
+            "--program",
+            &program,
+            "--current-authority",
+            &authority_1,
+            "--new-authority",
+            &authority_2,
+            "--timestamp",
+            "0",
+            "--export",
+            "message",
+            "--nonce",
+            &nonce.to_string(),
+            "--nonce-authority",
+            &nonce_authority.to_string(),
+            "--blockhash",
+            &durable_nonce.to_string(),
+        ])
+        .await;
+    let message: Message = decode(&exported, Encoding::Base64).unwrap();
+    assert_eq!(message.recent_blockhash, durable_nonce);
+    assert_eq!(message.account_keys[0], simulator.get_payer());
+    assert_eq!(
+        message,
+        Message::new_with_blockhash(
+            &[
+                system_instruction::advance_nonce_account(&nonce, &nonce_authority),
+                program_authority_client::commit_ix(
+                    &authority_keypair_1.pubkey(),
+                    &authority_keypair_2.pubkey(),
+                    &Pubkey::from_str(&program).unwrap(),
+                    0,
+                    None,
+                ),
+            ],
+            Some(&simulator.get_payer()),
+            &durable_nonce,
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_sign_and_submit() {
+    let (mut simulator, authority_keypair_1) = CliSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+
+    let program = simulator.get_program();
+    let authority_1 = authority_keypair_1.pubkey().to_string();
+    let authority_2 = authority_keypair_2.pubkey().to_string();
+
+    // Only the fee payer signs, the current authority signs later from the transaction file
+    let exported = simulator
+        .export(&[
+            "escrow",
+            "propose",
+            "--program",
+            &program,
+            "--current-authority",
+            &authority_1,
+            "--new-authority",
+            &authority_2,
+            "--export",
+            "transaction",
+        ])
+        .await;
+    let transaction_path = simulator.get_keypair_dir().join("propose.txt");
+    std::fs::write(&transaction_path, exported).unwrap();
+    assert!(!read_transaction_file(&transaction_path, Encoding::Base64)
+        .unwrap()
+        .is_signed());
+
+    simulator.sign(&transaction_path, &authority_keypair_1);
+    assert!(read_transaction_file(&transaction_path, Encoding::Base64)
+        .unwrap()
+        .is_signed());
+
+    simulator.submit(&transaction_path).await.unwrap();
+    simulator
+        .check_program_authority_matches(&program_authority_client::escrow_authority(
+            &authority_keypair_1.pubkey(),
+            &authority_keypair_2.pubkey(),
+        ))
+        .await;
+}
+
+#[tokio::test]
+async fn test_status() {
+    let (mut simulator, authority_keypair_1) = CliSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+
+    let program = simulator.get_program();
+    let authority_1_path = simulator.write_keypair(&authority_keypair_1);
+    let authority_2_path = simulator.write_keypair(&authority_keypair_2);
+    let authority_1 = authority_keypair_1.pubkey().to_string();
+    let authority_2 = authority_keypair_2.pubkey().to_string();
+
+    let address_book_path = simulator.get_keypair_dir().join("address-book.txt");
+    std::fs::write(
+        &address_book_path,
+        format!(
+            "# Team keys\n{authority_1} Team multisig\n\n{}\n",
+            Pubkey::new_unique()
+        ),
+    )
+    .unwrap();
+    let address_book = address_book_path.to_str().unwrap();
+
+    assert_eq!(
+        simulator
+            .status(&[
+                "status",
+                "--program",
+                &program,
+                "--address-book",
+                address_book
+            ])
+            .await
+            .unwrap(),
+        format!("Held directly by {authority_1} (Team multisig)")
+    );
+
+    simulator
+        .run(&[
+            "escrow",
+            "propose",
+            "--program",
+            &program,
+            "--current-authority",
+            &authority_1_path,
+            "--new-authority",
+            &authority_2,
+        ])
+        .await
+        .unwrap();
+
+    // The escrow can only be recognized once both authorities are candidates
+    let escrow_authority = program_authority_client::escrow_authority(
+        &authority_keypair_1.pubkey(),
+        &authority_keypair_2.pubkey(),
+    );
+    assert_eq!(
+        simulator
+            .status(&[
+                "status",
+                "--program",
+                &program,
+                "--address-book",
+                address_book
+            ])
+            .await
+            .unwrap(),
+        format!("Held directly by {escrow_authority}")
+    );
+    let escrowed = format!("In escrow from {authority_1} (Team multisig) to {authority_2}");
+    assert_eq!(
+        simulator
+            .status(&[
+                "status",
+                "--program",
+                &program,
+                "--address-book",
+                address_book,
+                "--candidate",
+                &authority_2,
+            ])
+            .await
+            .unwrap(),
+        escrowed
+    );
+
+    // Offline from a dump of the program data account
+    let program_data_address = simulator.get_program_data_address();
+    let dump = simulator.dump_program_data(&program_data_address).await;
+    assert_eq!(
+        simulator
+            .status(&[
+                "status",
+                "--program",
+                &program,
+                "--address-book",
+                address_book,
+                "--candidate",
+                &authority_2,
+                "--program-data-dump",
+                dump.to_str().unwrap(),
+            ])
+            .await
+            .unwrap(),
+        escrowed
+    );
+    let wrong_dump = simulator.dump_program_data(&Pubkey::new_unique()).await;
+    assert!(simulator
+        .status(&[
+            "status",
+            "--program",
+            &program,
+            "--program-data-dump",
+            wrong_dump.to_str().unwrap(),
+        ])
+        .await
+        .is_err());
+
+    simulator
+        .run(&[
+            "escrow",
+            "accept",
+            "--program",
+            &program,
+            "--current-authority",
+            &authority_1,
+            "--new-authority",
+            &authority_2_path,
+        ])
+        .await
+        .unwrap();
+
+    simulator.warp_to_timestamp(1700000000).await;
+    simulator
+        .run(&[
+            "timelock",
+            "commit",
+            "--program",
+            &program,
+            "--current-authority",
+            &authority_2_path,
+            "--new-authority",
+            &authority_1,
+            "--timestamp",
+            "1700000030",
+        ])
+        .await
+        .unwrap();
+    assert_eq!(
+        simulator
+            .status(&[
+                "status",
+                "--program",
+                &program,
+                "--address-book",
+                address_book,
+                "--timestamp",
+                "0",
+                "--timestamp",
+                "1700000030",
+            ])
+            .await
+            .unwrap(),
+        format!("Timelocked for {authority_1} (Team multisig) until 1700000030, not matured yet")
+    );
+}
+
+// `plan` and `apply` block on the bank from the test runtime
+#[tokio::test(flavor = "multi_thread")]
+async fn test_plan_and_apply() {
+    let (mut simulator, authority_keypair_1) = CliSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+
+    let program = simulator.get_program();
+    let authority_1 = authority_keypair_1.pubkey();
+    let authority_2 = authority_keypair_2.pubkey();
+
+    let escrow_plan = simulator.get_keypair_dir().join("escrow.toml");
+    std::fs::write(
+        &escrow_plan,
+        format!(
+            r#"
+[[handover]]
+program = "{program}"
+current_authority = "{authority_1}"
+new_authority = "{authority_2}"
+mode = "escrow"
+"#
+        ),
+    )
+    .unwrap();
+
+    assert_eq!(
+        simulator.plan(&escrow_plan).unwrap(),
+        format!(
+            "{program}: Held directly by {authority_1}\n  propose, signed by {authority_1}\n  \
+             accept, signed by {authority_2}\nSigners:\n  {authority_1}\n  {authority_2}"
+        )
+    );
+
+    // Nothing is sent when a signer is missing
+    assert!(simulator
+        .apply(&escrow_plan, &[&authority_keypair_1])
+        .is_err());
+    simulator
+        .check_program_authority_matches(&authority_1)
+        .await;
+
+    assert_eq!(
+        simulator
+            .apply(&escrow_plan, &[&authority_keypair_1, &authority_keypair_2])
+            .unwrap(),
+        vec![format!("{program} propose"), format!("{program} accept")]
+    );
+    simulator
+        .check_program_authority_matches(&authority_2)
+        .await;
+    assert_eq!(
+        simulator.plan(&escrow_plan).unwrap(),
+        format!("{program}: Held directly by {authority_2}\n  nothing to do\nSigners:")
+    );
+
+    let timelock_plan = simulator.get_keypair_dir().join("timelock.json");
+    let write_timelock_plan =
+        |current_authority: &Pubkey, new_authority: &Pubkey, timestamp: i64| {
+            let plan = serde_json::json!({
+                "handover": [{
+                    "program": program,
+                    "current_authority": current_authority.to_string(),
+                    "new_authority": new_authority.to_string(),
+                    "mode": "timelock",
+                    "timestamp": timestamp,
+                }],
+            });
+            std::fs::write(&timelock_plan, plan.to_string()).unwrap();
+        };
+
+    // The chain doesn't match a plan starting from the previous authority anymore
+    write_timelock_plan(&authority_1, &authority_2, 0);
+    assert!(simulator.plan(&timelock_plan).is_err());
+
+    write_timelock_plan(&authority_2, &authority_1, 0);
+    simulator
+        .apply(&timelock_plan, &[&authority_keypair_2])
+        .unwrap();
+    simulator
+        .check_program_authority_matches(&authority_1)
+        .await;
+
+    // The transfer waits and the program stays in the timelock
+    simulator.warp_to_timestamp(1700000000).await;
+    write_timelock_plan(&authority_1, &authority_2, 1700000000 + 30);
+    assert_eq!(
+        simulator.plan(&timelock_plan).unwrap(),
+        format!(
+            "{program}: Held directly by {authority_1}\n  commit, signed by {authority_1}\n  \
+             transfer, not before 1700000030\nSigners:\n  {authority_1}"
+        )
+    );
+    assert_eq!(
+        simulator
+            .apply(&timelock_plan, &[&authority_keypair_1])
+            .unwrap(),
+        vec![format!("{program} commit")]
+    );
+    simulator
+        .check_program_authority_matches(&program_authority_client::timelock_authority(
+            &authority_2,
+            1700000000 + 30,
+        ))
+        .await;
+    assert_eq!(
+        simulator.plan(&timelock_plan).unwrap(),
+        format!(
+            "{program}: Timelocked for {authority_2} until 1700000030, not matured yet\n  \
+             transfer, not before 1700000030\nSigners:"
+        )
+    );
+}
+
+#[test]
+fn test_history() {
+    let fixtures = Path::new("../tests/fixtures/history");
+    let transactions = [
+        read_transactions_file(&fixtures.join("transactions.json")).unwrap(),
+        read_transactions_file(&fixtures.join("transfer-response.json")).unwrap(),
+    ]
+    .concat();
+    let events: Vec<_> = transactions
+        .into_iter()
+        .flat_map(|transaction| decode_transaction(transaction).unwrap())
+        .collect();
+    assert_eq!(events.len(), 11);
+
+    let program = Pubkey::from_str("C6iRNYLu7yqVbF7aczpmVuCAXz7JTRUr3VEdtYg69wDa").unwrap();
+    let report = history_report(events.clone(), Some(&program), ReportFormat::Csv).unwrap();
+    let expected = std::fs::read_to_string(fixtures.join("report.csv")).unwrap();
+    assert_eq!(report, expected.trim_end());
+
+    // Without a program, the report groups the handovers by program in slot order
+    let report: Vec<serde_json::Value> =
+        serde_json::from_str(&history_report(events, None, ReportFormat::Json).unwrap()).unwrap();
+    assert_eq!(report.len(), 11);
+    let keys: Vec<_> = report
+        .iter()
+        .map(|event| {
+            (
+                Pubkey::from_str(event["program"].as_str().unwrap()).unwrap(),
+                event["slot"].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    let mut sorted = keys.clone();
+    sorted.sort();
+    assert_eq!(keys, sorted);
+    assert_eq!(report.last().unwrap()["instruction"], "transfer");
+    assert_eq!(
+        report.last().unwrap()["current_authority"],
+        serde_json::Value::Null
+    );
+
+    // Transactions of the same slot keep the order they were read in, oldest first
+    let same_slot: Vec<_> = report
+        .iter()
+        .filter(|event| event["slot"].as_u64() == Some(1020))
+        .map(|event| event["instruction"].as_str().unwrap())
+        .collect();
+    assert_eq!(same_slot, vec!["propose", "revert"]);
+}
+
+/// Runs with `cargo test --features simulate`
+#[cfg(feature = "simulate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_simulate() {
+    let (mut simulator, authority_keypair_1) = CliSimulator::new().await;
+    let authority_1 = authority_keypair_1.pubkey();
+    let authority_2 = Pubkey::new_unique();
+    let program = simulator.get_program();
+    let blockhash = Hash::new_unique().to_string();
+    let escrow_authority = program_authority_client::escrow_authority(&authority_1, &authority_2);
+
+    // Only public keys are given, like before signing with a hardware wallet
+    let propose: [&str; 10] = [
+        "escrow",
+        "propose",
+        "--program",
+        &program,
+        "--current-authority",
+        &authority_1.to_string(),
+        "--new-authority",
+        &authority_2.to_string(),
+        "--blockhash",
+        &blockhash,
+    ];
+    let summary = simulator
+        .simulate(&[&propose[..], &["--simulate"][..]].concat())
+        .unwrap();
+    let exported = simulator
+        .export(&[&propose[..], &["--export", "message"][..]].concat())
+        .await;
+    let message: Message = decode(&exported, Encoding::Base64).unwrap();
+    assert!(summary.starts_with(&format!(
+        "Message hash: {}\nFee payer: {}\nInstruction: propose\nProgram: {program}\n",
+        solana_sdk::hash::hash(&message.serialize()),
+        simulator.get_payer()
+    )));
+    assert!(summary.contains(&format!(
+        "Upgrade authority before: {authority_1}\n  Held directly by {authority_1}\n"
+    )));
+    assert!(summary.contains(&format!(
+        "Upgrade authority after: {escrow_authority}\n  In escrow from {authority_1} to \
+         {authority_2}\nResult: success\nLogs:\n"
+    )));
+    // Nothing was sent
+    simulator
+        .check_program_authority_matches(&authority_1)
+        .await;
+
+    // Accepting before the proposal fails and leaves the upgrade authority where it was
+    let summary = simulator
+        .simulate(&[
+            "escrow",
+            "accept",
+            "--program",
+            &program,
+            "--current-authority",
+            &authority_1.to_string(),
+            "--new-authority",
+            &authority_2.to_string(),
+            "--simulate",
+        ])
+        .unwrap();
+    assert!(summary.contains(&format!(
+        "Upgrade authority after: {authority_1}\n  Held directly by {authority_1}\nResult: \
+         failed"
+    )));
+
+    // Timelocks are checked against the clock of the copied bank
+    simulator.warp_to_timestamp(1700000000).await;
+    let summary = simulator
+        .simulate(&[
+            "timelock",
+            "commit",
+            "--program",
+            &program,
+            "--current-authority",
+            &authority_1.to_string(),
+            "--new-authority",
+            &authority_2.to_string(),
+            "--timestamp",
+            "1700000030",
+            "--simulate",
+        ])
+        .unwrap();
+    assert!(summary.contains(&format!(
+        "Upgrade authority after: {}\n  Timelocked for {authority_2} until 1700000030, not \
+         matured yet\nResult: success",
+        program_authority_client::timelock_authority(&authority_2, 1700000030)
+    )));
+}
@@ -0,0 +1,66 @@
+use {
+    crate::{
+        decode_upgrade_authority,
+        program_data_address,
+    },
+    anyhow::{
+        anyhow,
+        Result,
+    },
+    solana_client::rpc_client::RpcClient,
+    solana_sdk::{
+        account::{
+            from_account,
+            Account,
+        },
+        clock::Clock,
+        hash::Hash,
+        pubkey::Pubkey,
+        signature::Signature,
+        sysvar,
+        transaction::Transaction,
+    },
+};
+
+/// Where the tools read accounts from and send transactions to : the RPC client in the binaries,
+/// a local bank in the tests
+pub trait Cluster {
+    /// The account, `None` if it doesn't exist
+    fn get_account(&self, address: &Pubkey) -> Result<Option<Account>>;
+
+    fn get_latest_blockhash(&self) -> Result<Hash>;
+
+    /// Sends the transaction and waits for its confirmation
+    fn send_transaction(&self, transaction: &Transaction) -> Result<Signature>;
+
+    fn get_clock(&self) -> Result<Clock> {
+        let account = self
+            .get_account(&sysvar::clock::id())?
+            .ok_or_else(|| anyhow!("Clock sysvar not found"))?;
+        from_account(&account).ok_or_else(|| anyhow!("Failed to decode the clock sysvar"))
+    }
+
+    /// Upgrade authority of the program, `None` once it is immutable
+    fn get_upgrade_authority(&self, program: &Pubkey) -> Result<Option<Pubkey>> {
+        let account = self
+            .get_account(&program_data_address(program))?
+            .ok_or_else(|| anyhow!("Program data account of {program} not found"))?;
+        Ok(decode_upgrade_authority(&account.data)?)
+    }
+}
+
+impl Cluster for RpcClient {
+    fn get_account(&self, address: &Pubkey) -> Result<Option<Account>> {
+        Ok(self
+            .get_account_with_commitment(address, self.commitment())?
+            .value)
+    }
+
+    fn get_latest_blockhash(&self) -> Result<Hash> {
+        Ok(RpcClient::get_latest_blockhash(self)?)
+    }
+
+    fn send_transaction(&self, transaction: &Transaction) -> Result<Signature> {
+        Ok(self.send_and_confirm_transaction(transaction)?)
+    }
+}
@@ -0,0 +1,715 @@
+use {
+    crate::offline::DecodeError,
+    anchor_lang::{
+        prelude::Pubkey,
+        solana_program::{
+            hash::Hash,
+            instruction::AccountMeta,
+        },
+        AnchorDeserialize,
+        Discriminator,
+        ToAccountMetas,
+    },
+    program_authority_common::HandoverMemo,
+    serde::Deserialize,
+    std::str::FromStr,
+};
+
+/// A handover instruction sent to the escrow or the timelock, with its accounts resolved to roles
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodedHandover {
+    /// Name of the instruction in its program, like `propose` or `transfer_checked`
+    pub instruction:       &'static str,
+    pub program_id:        Pubkey,
+    pub program_account:   Pubkey,
+    /// The timelock's transfers don't take the previous authority
+    pub current_authority: Option<Pubkey>,
+    /// The confirm authority for the immutable instructions
+    pub new_authority:     Pubkey,
+    /// Where `accept_and_propose` and `accept_to_timelock` hand the program next
+    pub next_authority:    Option<Pubkey>,
+    pub timestamp:         Option<i64>,
+    /// Nonce of the scoped escrow instructions
+    pub nonce:             Option<u64>,
+    /// The reason, or the reference hash prefixed with `hash:`
+    pub memo:              Option<String>,
+}
+
+/// A decoded handover along with the transaction it was found in
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HandoverEvent {
+    pub signature:  String,
+    pub slot:       u64,
+    pub block_time: Option<i64>,
+    /// Failed transactions are kept, they show attempted handovers
+    pub success:    bool,
+    pub handover:   DecodedHandover,
+}
+
+/// Positions of the roles in the account metas of an `Accounts` struct
+struct Roles {
+    current_authority: Option<usize>,
+    new_authority:     usize,
+    next_authority:    Option<usize>,
+    program_account:   usize,
+}
+
+/// Fills a role of an `Accounts` struct so that its position can be found in the account metas
+fn marker(role: u8) -> Pubkey {
+    Pubkey::new_from_array([role; 32])
+}
+
+const OTHER: u8 = 0;
+const CURRENT_AUTHORITY: u8 = 1;
+const NEW_AUTHORITY: u8 = 2;
+const PROGRAM_ACCOUNT: u8 = 3;
+const NEXT_AUTHORITY: u8 = 4;
+
+impl Roles {
+    fn resolve(metas: Vec<AccountMeta>) -> Self {
+        let position = |role| metas.iter().position(|meta| meta.pubkey == marker(role));
+        Roles {
+            current_authority: position(CURRENT_AUTHORITY),
+            new_authority:     position(NEW_AUTHORITY).expect("Every handover has a new authority"),
+            next_authority:    position(NEXT_AUTHORITY),
+            program_account:   position(PROGRAM_ACCOUNT).expect("Every handover has a program"),
+        }
+    }
+
+    fn escrow_propose() -> Self {
+        Self::resolve(
+            program_authority_escrow::accounts::Propose {
+                current_authority:     marker(CURRENT_AUTHORITY),
+                new_authority:         marker(NEW_AUTHORITY),
+                escrow_authority:      marker(OTHER),
+                program_account:       marker(PROGRAM_ACCOUNT),
+                program_data:          marker(OTHER),
+                bpf_upgradable_loader: marker(OTHER),
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    fn escrow_propose_with_record() -> Self {
+        Self::resolve(
+            program_authority_escrow::accounts::ProposeWithRecord {
+                current_authority:     marker(CURRENT_AUTHORITY),
+                new_authority:         marker(NEW_AUTHORITY),
+                escrow_authority:      marker(OTHER),
+                proposal_record:       marker(OTHER),
+                payer:                 marker(OTHER),
+                program_account:       marker(PROGRAM_ACCOUNT),
+                program_data:          marker(OTHER),
+                system_program:        marker(OTHER),
+                bpf_upgradable_loader: marker(OTHER),
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    fn escrow_accept() -> Self {
+        Self::resolve(
+            program_authority_escrow::accounts::Accept {
+                current_authority:     marker(CURRENT_AUTHORITY),
+                new_authority:         marker(NEW_AUTHORITY),
+                escrow_authority:      marker(OTHER),
+                program_account:       marker(PROGRAM_ACCOUNT),
+                program_data:          marker(OTHER),
+                bpf_upgradable_loader: marker(OTHER),
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    fn escrow_accept_and_propose() -> Self {
+        Self::resolve(
+            program_authority_escrow::accounts::AcceptAndPropose {
+                current_authority:     marker(CURRENT_AUTHORITY),
+                new_authority:         marker(NEW_AUTHORITY),
+                escrow_authority:      marker(OTHER),
+                next_authority:        marker(NEXT_AUTHORITY),
+                next_escrow_authority: marker(OTHER),
+                program_account:       marker(PROGRAM_ACCOUNT),
+                program_data:          marker(OTHER),
+                bpf_upgradable_loader: marker(OTHER),
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    fn escrow_accept_to_timelock() -> Self {
+        Self::resolve(
+            program_authority_escrow::accounts::AcceptToTimelock {
+                current_authority:     marker(CURRENT_AUTHORITY),
+                new_authority:         marker(NEW_AUTHORITY),
+                escrow_authority:      marker(OTHER),
+                final_authority:       marker(NEXT_AUTHORITY),
+                timelock_authority:    marker(OTHER),
+                program_account:       marker(PROGRAM_ACCOUNT),
+                program_data:          marker(OTHER),
+                bpf_upgradable_loader: marker(OTHER),
+                timelock_program:      marker(OTHER),
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    fn escrow_propose_scoped() -> Self {
+        Self::resolve(
+            program_authority_escrow::accounts::ProposeScoped {
+                current_authority:     marker(CURRENT_AUTHORITY),
+                new_authority:         marker(NEW_AUTHORITY),
+                escrow_authority:      marker(OTHER),
+                program_account:       marker(PROGRAM_ACCOUNT),
+                program_data:          marker(OTHER),
+                bpf_upgradable_loader: marker(OTHER),
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    fn escrow_accept_scoped() -> Self {
+        Self::resolve(
+            program_authority_escrow::accounts::AcceptScoped {
+                current_authority:     marker(CURRENT_AUTHORITY),
+                new_authority:         marker(NEW_AUTHORITY),
+                escrow_authority:      marker(OTHER),
+                program_account:       marker(PROGRAM_ACCOUNT),
+                program_data:          marker(OTHER),
+                bpf_upgradable_loader: marker(OTHER),
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    fn escrow_upgrade() -> Self {
+        Self::resolve(
+            program_authority_escrow::accounts::Upgrade {
+                current_authority:     marker(CURRENT_AUTHORITY),
+                new_authority:         marker(NEW_AUTHORITY),
+                escrow_authority:      marker(OTHER),
+                program_account:       marker(PROGRAM_ACCOUNT),
+                program_data:          marker(OTHER),
+                buffer:                marker(OTHER),
+                spill:                 marker(OTHER),
+                rent:                  marker(OTHER),
+                clock:                 marker(OTHER),
+                bpf_upgradable_loader: marker(OTHER),
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    /// The confirm authority takes the place of the new authority
+    fn escrow_propose_immutable() -> Self {
+        Self::resolve(
+            program_authority_escrow::accounts::ProposeImmutable {
+                current_authority:     marker(CURRENT_AUTHORITY),
+                confirm_authority:     marker(NEW_AUTHORITY),
+                immutable_authority:   marker(OTHER),
+                program_account:       marker(PROGRAM_ACCOUNT),
+                program_data:          marker(OTHER),
+                bpf_upgradable_loader: marker(OTHER),
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    fn escrow_confirm_immutable() -> Self {
+        Self::resolve(
+            program_authority_escrow::accounts::ConfirmImmutable {
+                current_authority:     marker(CURRENT_AUTHORITY),
+                confirm_authority:     marker(NEW_AUTHORITY),
+                immutable_authority:   marker(OTHER),
+                program_account:       marker(PROGRAM_ACCOUNT),
+                program_data:          marker(OTHER),
+                bpf_upgradable_loader: marker(OTHER),
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    fn timelock_commit() -> Self {
+        Self::resolve(
+            program_authority_timelock::accounts::Commit {
+                current_authority:     marker(CURRENT_AUTHORITY),
+                new_authority:         marker(NEW_AUTHORITY),
+                escrow_authority:      marker(OTHER),
+                program_account:       marker(PROGRAM_ACCOUNT),
+                program_data:          marker(OTHER),
+                bpf_upgradable_loader: marker(OTHER),
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    fn timelock_commit_with_record() -> Self {
+        Self::resolve(
+            program_authority_timelock::accounts::CommitWithRecord {
+                current_authority:     marker(CURRENT_AUTHORITY),
+                new_authority:         marker(NEW_AUTHORITY),
+                escrow_authority:      marker(OTHER),
+                commitment_record:     marker(OTHER),
+                payer:                 marker(OTHER),
+                program_account:       marker(PROGRAM_ACCOUNT),
+                program_data:          marker(OTHER),
+                system_program:        marker(OTHER),
+                bpf_upgradable_loader: marker(OTHER),
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    fn timelock_transfer() -> Self {
+        Self::resolve(
+            program_authority_timelock::accounts::Transfer {
+                new_authority:         marker(NEW_AUTHORITY),
+                escrow_authority:      marker(OTHER),
+                program_account:       marker(PROGRAM_ACCOUNT),
+                program_data:          marker(OTHER),
+                bpf_upgradable_loader: marker(OTHER),
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    fn timelock_transfer_checked() -> Self {
+        Self::resolve(
+            program_authority_timelock::accounts::TransferChecked {
+                new_authority:         marker(NEW_AUTHORITY),
+                escrow_authority:      marker(OTHER),
+                program_account:       marker(PROGRAM_ACCOUNT),
+                program_data:          marker(OTHER),
+                bpf_upgradable_loader: marker(OTHER),
+            }
+            .to_account_metas(None),
+        )
+    }
+}
+
+fn memo_text(memo: Option<HandoverMemo>) -> Option<String> {
+    memo.map(|memo| match memo {
+        HandoverMemo::Reason(reason) => reason,
+        HandoverMemo::Hash(hash) => format!("hash:{}", Hash::new_from_array(hash)),
+    })
+}
+
+/// Decodes an instruction through the Anchor discriminators of the escrow and timelock handover
+/// instructions, every instruction that moves the upgrade authority or upgrades the program from
+/// escrow. Returns `None` for any other instruction, or if the accounts or the arguments don't
+/// match
+pub fn decode_handover(
+    program_id: &Pubkey,
+    accounts: &[Pubkey],
+    data: &[u8],
+) -> Option<DecodedHandover> {
+    if data.len() < 8 {
+        return None;
+    }
+    let (discriminator, mut args) = data.split_at(8);
+
+    let (instruction, roles, timestamp, nonce, memo) =
+        if *program_id == program_authority_escrow::id() {
+            use program_authority_escrow::instruction;
+
+            match discriminator {
+                d if d == instruction::Propose::DISCRIMINATOR => {
+                    ("propose", Roles::escrow_propose(), None, None, None)
+                }
+                d if d == instruction::ProposeWithMemo::DISCRIMINATOR => {
+                    let args = instruction::ProposeWithMemo::deserialize(&mut args).ok()?;
+                    (
+                        "propose_with_memo",
+                        Roles::escrow_propose(),
+                        None,
+                        None,
+                        memo_text(Some(args.memo)),
+                    )
+                }
+                d if d == instruction::ProposeStrict::DISCRIMINATOR => {
+                    let args = instruction::ProposeStrict::deserialize(&mut args).ok()?;
+                    (
+                        "propose_strict",
+                        Roles::escrow_propose(),
+                        None,
+                        None,
+                        memo_text(args.memo),
+                    )
+                }
+                d if d == instruction::ProposeWithRecord::DISCRIMINATOR => {
+                    let args = instruction::ProposeWithRecord::deserialize(&mut args).ok()?;
+                    (
+                        "propose_with_record",
+                        Roles::escrow_propose_with_record(),
+                        None,
+                        None,
+                        memo_text(args.memo),
+                    )
+                }
+                d if d == instruction::Revert::DISCRIMINATOR => {
+                    ("revert", Roles::escrow_propose(), None, None, None)
+                }
+                d if d == instruction::RevertWithMemo::DISCRIMINATOR => {
+                    let args = instruction::RevertWithMemo::deserialize(&mut args).ok()?;
+                    (
+                        "revert_with_memo",
+                        Roles::escrow_propose(),
+                        None,
+                        None,
+                        memo_text(Some(args.memo)),
+                    )
+                }
+                d if d == instruction::Accept::DISCRIMINATOR => {
+                    ("accept", Roles::escrow_accept(), None, None, None)
+                }
+                d if d == instruction::AcceptWithMemo::DISCRIMINATOR => {
+                    let args = instruction::AcceptWithMemo::deserialize(&mut args).ok()?;
+                    (
+                        "accept_with_memo",
+                        Roles::escrow_accept(),
+                        None,
+                        None,
+                        memo_text(Some(args.memo)),
+                    )
+                }
+                d if d == instruction::AcceptChecked::DISCRIMINATOR => {
+                    let args = instruction::AcceptChecked::deserialize(&mut args).ok()?;
+                    (
+                        "accept_checked",
+                        Roles::escrow_accept(),
+                        None,
+                        None,
+                        memo_text(args.memo),
+                    )
+                }
+                d if d == instruction::AcceptAndPropose::DISCRIMINATOR => {
+                    let args = instruction::AcceptAndPropose::deserialize(&mut args).ok()?;
+                    (
+                        "accept_and_propose",
+                        Roles::escrow_accept_and_propose(),
+                        None,
+                        None,
+                        memo_text(args.memo),
+                    )
+                }
+                d if d == instruction::AcceptToTimelock::DISCRIMINATOR => {
+                    let args = instruction::AcceptToTimelock::deserialize(&mut args).ok()?;
+                    (
+                        "accept_to_timelock",
+                        Roles::escrow_accept_to_timelock(),
+                        Some(args.timestamp),
+                        None,
+                        memo_text(args.memo),
+                    )
+                }
+                d if d == instruction::ProposeScoped::DISCRIMINATOR => {
+                    let args = instruction::ProposeScoped::deserialize(&mut args).ok()?;
+                    (
+                        "propose_scoped",
+                        Roles::escrow_propose_scoped(),
+                        None,
+                        Some(args.nonce),
+                        memo_text(args.memo),
+                    )
+                }
+                d if d == instruction::RevertScoped::DISCRIMINATOR => {
+                    let args = instruction::RevertScoped::deserialize(&mut args).ok()?;
+                    (
+                        "revert_scoped",
+                        Roles::escrow_propose_scoped(),
+                        None,
+                        Some(args.nonce),
+                        memo_text(args.memo),
+                    )
+                }
+                d if d == instruction::AcceptScoped::DISCRIMINATOR => {
+                    let args = instruction::AcceptScoped::deserialize(&mut args).ok()?;
+                    (
+                        "accept_scoped",
+                        Roles::escrow_accept_scoped(),
+                        None,
+                        Some(args.nonce),
+                        memo_text(args.memo),
+                    )
+                }
+                d if d == instruction::Upgrade::DISCRIMINATOR => {
+                    ("upgrade", Roles::escrow_upgrade(), None, None, None)
+                }
+                d if d == instruction::ProposeImmutable::DISCRIMINATOR => (
+                    "propose_immutable",
+                    Roles::escrow_propose_immutable(),
+                    None,
+                    None,
+                    None,
+                ),
+                d if d == instruction::RevertImmutable::DISCRIMINATOR => (
+                    "revert_immutable",
+                    Roles::escrow_propose_immutable(),
+                    None,
+                    None,
+                    None,
+                ),
+                d if d == instruction::ConfirmImmutable::DISCRIMINATOR => (
+                    "confirm_immutable",
+                    Roles::escrow_confirm_immutable(),
+                    None,
+                    None,
+                    None,
+                ),
+                // `status`, `assert_authority`, `extend_program` and `close_proposal_record` don't
+                // move the upgrade authority
+                _ => return None,
+            }
+        } else if *program_id == program_authority_timelock::id() {
+            use program_authority_timelock::instruction;
+
+            match discriminator {
+                d if d == instruction::Commit::DISCRIMINATOR => {
+                    let args = instruction::Commit::deserialize(&mut args).ok()?;
+                    (
+                        "commit",
+                        Roles::timelock_commit(),
+                        Some(args.timestamp),
+                        None,
+                        None,
+                    )
+                }
+                d if d == instruction::CommitWithMemo::DISCRIMINATOR => {
+                    let args = instruction::CommitWithMemo::deserialize(&mut args).ok()?;
+                    (
+                        "commit_with_memo",
+                        Roles::timelock_commit(),
+                        Some(args.timestamp),
+                        None,
+                        memo_text(Some(args.memo)),
+                    )
+                }
+                d if d == instruction::CommitStrict::DISCRIMINATOR => {
+                    let args = instruction::CommitStrict::deserialize(&mut args).ok()?;
+                    (
+                        "commit_strict",
+                        Roles::timelock_commit(),
+                        Some(args.timestamp),
+                        None,
+                        memo_text(args.memo),
+                    )
+                }
+                d if d == instruction::CommitWithRecord::DISCRIMINATOR => {
+                    let args = instruction::CommitWithRecord::deserialize(&mut args).ok()?;
+                    (
+                        "commit_with_record",
+                        Roles::timelock_commit_with_record(),
+                        Some(args.timestamp),
+                        None,
+                        memo_text(args.memo),
+                    )
+                }
+                d if d == instruction::Transfer::DISCRIMINATOR => {
+                    let args = instruction::Transfer::deserialize(&mut args).ok()?;
+                    (
+                        "transfer",
+                        Roles::timelock_transfer(),
+                        Some(args.timestamp),
+                        None,
+                        None,
+                    )
+                }
+                d if d == instruction::TransferWithMemo::DISCRIMINATOR => {
+                    let args = instruction::TransferWithMemo::deserialize(&mut args).ok()?;
+                    (
+                        "transfer_with_memo",
+                        Roles::timelock_transfer(),
+                        Some(args.timestamp),
+                        None,
+                        memo_text(Some(args.memo)),
+                    )
+                }
+                d if d == instruction::TransferChecked::DISCRIMINATOR => {
+                    let args = instruction::TransferChecked::deserialize(&mut args).ok()?;
+                    (
+                        "transfer_checked",
+                        Roles::timelock_transfer_checked(),
+                        Some(args.timestamp),
+                        None,
+                        memo_text(args.memo),
+                    )
+                }
+                // `status`, `extend_program` and `close_commitment_record` don't move the upgrade
+                // authority
+                _ => return None,
+            }
+        } else {
+            return None;
+        };
+
+    Some(DecodedHandover {
+        instruction,
+        program_id: *program_id,
+        program_account: *accounts.get(roles.program_account)?,
+        current_authority: match roles.current_authority {
+            Some(position) => Some(*accounts.get(position)?),
+            None => None,
+        },
+        new_authority: *accounts.get(roles.new_authority)?,
+        next_authority: match roles.next_authority {
+            Some(position) => Some(*accounts.get(position)?),
+            None => None,
+        },
+        timestamp,
+        nonce,
+        memo,
+    })
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecordedTransaction {
+    slot:        u64,
+    block_time:  Option<i64>,
+    transaction: RecordedTransactionBody,
+    meta:        Option<RecordedMeta>,
+}
+
+#[derive(Deserialize)]
+struct RecordedTransactionBody {
+    signatures: Vec<String>,
+    message:    RecordedMessage,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecordedMessage {
+    account_keys: Vec<String>,
+    instructions: Vec<RecordedInstruction>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecordedInstruction {
+    program_id_index: u8,
+    accounts:         Vec<u8>,
+    data:             String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecordedMeta {
+    err:                Option<serde_json::Value>,
+    #[serde(default)]
+    inner_instructions: Option<Vec<RecordedInnerInstructions>>,
+    #[serde(default)]
+    loaded_addresses:   Option<RecordedLoadedAddresses>,
+}
+
+#[derive(Deserialize)]
+struct RecordedInnerInstructions {
+    index:        u8,
+    instructions: Vec<RecordedInstruction>,
+}
+
+#[derive(Deserialize)]
+struct RecordedLoadedAddresses {
+    writable: Vec<String>,
+    readonly: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum HistoryError {
+    Json(serde_json::Error),
+    Decode(DecodeError),
+    InvalidPubkey(String),
+    InvalidAccountIndex(u8),
+}
+
+impl std::fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            HistoryError::Json(err) => write!(f, "Invalid transaction JSON: {err}"),
+            HistoryError::Decode(err) => write!(f, "{err}"),
+            HistoryError::InvalidPubkey(key) => write!(f, "Invalid public key {key}"),
+            HistoryError::InvalidAccountIndex(index) => {
+                write!(f, "Account index {index} is out of the account keys")
+            }
+        }
+    }
+}
+
+impl std::error::Error for HistoryError {
+}
+
+/// Decodes the handovers of a `getTransaction` result with the `json` encoding, the instructions
+/// sent through CPI right after the instruction that sent them
+pub fn decode_transaction(
+    transaction: serde_json::Value,
+) -> Result<Vec<HandoverEvent>, HistoryError> {
+    let transaction: RecordedTransaction =
+        serde_json::from_value(transaction).map_err(HistoryError::Json)?;
+
+    let mut account_keys = transaction.transaction.message.account_keys.clone();
+    let meta = transaction.meta.as_ref();
+    if let Some(loaded_addresses) = meta.and_then(|meta| meta.loaded_addresses.as_ref()) {
+        account_keys.extend(loaded_addresses.writable.iter().cloned());
+        account_keys.extend(loaded_addresses.readonly.iter().cloned());
+    }
+    let account_keys = account_keys
+        .iter()
+        .map(|key| Pubkey::from_str(key).map_err(|_| HistoryError::InvalidPubkey(key.clone())))
+        .collect::<Result<Vec<_>, _>>()?;
+    let inner_instructions = meta
+        .and_then(|meta| meta.inner_instructions.as_deref())
+        .unwrap_or_default();
+
+    let mut events = vec![];
+    for (index, instruction) in transaction
+        .transaction
+        .message
+        .instructions
+        .iter()
+        .enumerate()
+    {
+        let inner = inner_instructions
+            .iter()
+            .filter(|inner| inner.index as usize == index)
+            .flat_map(|inner| inner.instructions.iter());
+        for instruction in std::iter::once(instruction).chain(inner) {
+            if let Some(handover) = decode_recorded_instruction(instruction, &account_keys)? {
+                events.push(HandoverEvent {
+                    signature: transaction
+                        .transaction
+                        .signatures
+                        .first()
+                        .cloned()
+                        .unwrap_or_default(),
+                    slot: transaction.slot,
+                    block_time: transaction.block_time,
+                    success: meta.map_or(true, |meta| meta.err.is_none()),
+                    handover,
+                });
+            }
+        }
+    }
+    Ok(events)
+}
+
+fn decode_recorded_instruction(
+    instruction: &RecordedInstruction,
+    account_keys: &[Pubkey],
+) -> Result<Option<DecodedHandover>, HistoryError> {
+    let key = |index: u8| {
+        account_keys
+            .get(index as usize)
+            .copied()
+            .ok_or(HistoryError::InvalidAccountIndex(index))
+    };
+    let program_id = key(instruction.program_id_index)?;
+    let accounts = instruction
+        .accounts
+        .iter()
+        .map(|index| key(*index))
+        .collect::<Result<Vec<_>, _>>()?;
+    let data = bs58::decode(&instruction.data)
+        .into_vec()
+        .map_err(|err| HistoryError::Decode(DecodeError::Base58(err)))?;
+
+    Ok(decode_handover(&program_id, &accounts, &data))
+}
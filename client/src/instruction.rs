@@ -0,0 +1,807 @@
+use {
+    crate::pda::{
+        commitment_record_address,
+        escrow_authority,
+        immutable_authority,
+        program_data_address,
+        proposal_record_address,
+        scoped_escrow_authority,
+        timelock_authority,
+    },
+    anchor_lang::{
+        prelude::Pubkey,
+        solana_program::{
+            bpf_loader_upgradeable,
+            instruction::Instruction,
+            system_program,
+            sysvar,
+        },
+        InstructionData,
+        ToAccountMetas,
+    },
+    program_authority_common::HandoverMemo,
+};
+
+/// Moves the upgrade authority of `program` from `current_authority` to the escrow, through
+/// `propose_with_memo` when given a memo
+pub fn propose_ix(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    program: &Pubkey,
+    memo: Option<HandoverMemo>,
+) -> Instruction {
+    match memo {
+        Some(memo) => propose_with_memo_ix(current_authority, new_authority, program, memo),
+        None => Instruction {
+            program_id: program_authority_escrow::id(),
+            accounts:   escrow_propose_accounts(current_authority, new_authority, program)
+                .to_account_metas(None),
+            data:       program_authority_escrow::instruction::Propose.data(),
+        },
+    }
+}
+
+pub fn propose_with_memo_ix(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    program: &Pubkey,
+    memo: HandoverMemo,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_escrow::id(),
+        accounts:   escrow_propose_accounts(current_authority, new_authority, program)
+            .to_account_metas(None),
+        data:       program_authority_escrow::instruction::ProposeWithMemo { memo }.data(),
+    }
+}
+
+/// Same as `propose_ix`, but the program rejects new authorities that are neither wallets nor
+/// owned by an allowlisted program
+pub fn propose_strict_ix(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    program: &Pubkey,
+    memo: Option<HandoverMemo>,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_escrow::id(),
+        accounts:   escrow_propose_accounts(current_authority, new_authority, program)
+            .to_account_metas(None),
+        data:       program_authority_escrow::instruction::ProposeStrict { memo }.data(),
+    }
+}
+
+/// Same as `propose_ix`, along with a proposal record whose rent `payer` gets back on close
+pub fn propose_with_record_ix(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    payer: &Pubkey,
+    program: &Pubkey,
+    memo: Option<HandoverMemo>,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_escrow::id(),
+        accounts:   program_authority_escrow::accounts::ProposeWithRecord {
+            current_authority:     *current_authority,
+            new_authority:         *new_authority,
+            escrow_authority:      escrow_authority(current_authority, new_authority),
+            proposal_record:       proposal_record_address(
+                current_authority,
+                new_authority,
+                program,
+            ),
+            payer:                 *payer,
+            program_account:       *program,
+            program_data:          program_data_address(program),
+            system_program:        system_program::id(),
+            bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+        }
+        .to_account_metas(None),
+        data:       program_authority_escrow::instruction::ProposeWithRecord { memo }.data(),
+    }
+}
+
+/// Closes the proposal record once the proposal is settled, the rent goes back to `rent_payer`
+pub fn close_proposal_record_ix(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    rent_payer: &Pubkey,
+    program: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_escrow::id(),
+        accounts:   program_authority_escrow::accounts::CloseProposalRecord {
+            proposal_record:       proposal_record_address(
+                current_authority,
+                new_authority,
+                program,
+            ),
+            rent_payer:            *rent_payer,
+            escrow_authority:      escrow_authority(current_authority, new_authority),
+            program_account:       *program,
+            program_data:          program_data_address(program),
+            bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+        }
+        .to_account_metas(None),
+        data:       program_authority_escrow::instruction::CloseProposalRecord.data(),
+    }
+}
+
+/// Gives the upgrade authority of `program` back to `current_authority`, through
+/// `revert_with_memo` when given a memo
+pub fn revert_ix(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    program: &Pubkey,
+    memo: Option<HandoverMemo>,
+) -> Instruction {
+    match memo {
+        Some(memo) => revert_with_memo_ix(current_authority, new_authority, program, memo),
+        None => Instruction {
+            program_id: program_authority_escrow::id(),
+            accounts:   escrow_propose_accounts(current_authority, new_authority, program)
+                .to_account_metas(None),
+            data:       program_authority_escrow::instruction::Revert.data(),
+        },
+    }
+}
+
+pub fn revert_with_memo_ix(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    program: &Pubkey,
+    memo: HandoverMemo,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_escrow::id(),
+        accounts:   escrow_propose_accounts(current_authority, new_authority, program)
+            .to_account_metas(None),
+        data:       program_authority_escrow::instruction::RevertWithMemo { memo }.data(),
+    }
+}
+
+/// Gives the upgrade authority of `program` to `new_authority`, who has to sign, through
+/// `accept_with_memo` when given a memo
+pub fn accept_ix(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    program: &Pubkey,
+    memo: Option<HandoverMemo>,
+) -> Instruction {
+    match memo {
+        Some(memo) => accept_with_memo_ix(current_authority, new_authority, program, memo),
+        None => Instruction {
+            program_id: program_authority_escrow::id(),
+            accounts:   escrow_accept_accounts(current_authority, new_authority, program)
+                .to_account_metas(None),
+            data:       program_authority_escrow::instruction::Accept.data(),
+        },
+    }
+}
+
+pub fn accept_with_memo_ix(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    program: &Pubkey,
+    memo: HandoverMemo,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_escrow::id(),
+        accounts:   escrow_accept_accounts(current_authority, new_authority, program)
+            .to_account_metas(None),
+        data:       program_authority_escrow::instruction::AcceptWithMemo { memo }.data(),
+    }
+}
+
+/// Same as `accept_ix`, but the program checks the new authority like `propose_strict`
+pub fn accept_checked_ix(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    program: &Pubkey,
+    memo: Option<HandoverMemo>,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_escrow::id(),
+        accounts:   escrow_accept_accounts(current_authority, new_authority, program)
+            .to_account_metas(None),
+        data:       program_authority_escrow::instruction::AcceptChecked { memo }.data(),
+    }
+}
+
+/// Accepts `program` as `new_authority` and proposes it to `next_authority` in one instruction
+pub fn accept_and_propose_ix(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    next_authority: &Pubkey,
+    program: &Pubkey,
+    memo: Option<HandoverMemo>,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_escrow::id(),
+        accounts:   program_authority_escrow::accounts::AcceptAndPropose {
+            current_authority:     *current_authority,
+            new_authority:         *new_authority,
+            escrow_authority:      escrow_authority(current_authority, new_authority),
+            next_authority:        *next_authority,
+            next_escrow_authority: escrow_authority(new_authority, next_authority),
+            program_account:       *program,
+            program_data:          program_data_address(program),
+            bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+        }
+        .to_account_metas(None),
+        data:       program_authority_escrow::instruction::AcceptAndPropose { memo }.data(),
+    }
+}
+
+/// Accepts `program` as `new_authority` and commits it to the timelock of `final_authority`
+/// until `timestamp` in one instruction
+pub fn accept_to_timelock_ix(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    final_authority: &Pubkey,
+    program: &Pubkey,
+    timestamp: i64,
+    memo: Option<HandoverMemo>,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_escrow::id(),
+        accounts:   program_authority_escrow::accounts::AcceptToTimelock {
+            current_authority:     *current_authority,
+            new_authority:         *new_authority,
+            escrow_authority:      escrow_authority(current_authority, new_authority),
+            final_authority:       *final_authority,
+            timelock_authority:    timelock_authority(final_authority, timestamp),
+            program_account:       *program,
+            program_data:          program_data_address(program),
+            bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+            timelock_program:      program_authority_timelock::id(),
+        }
+        .to_account_metas(None),
+        data:       program_authority_escrow::instruction::AcceptToTimelock { timestamp, memo }
+            .data(),
+    }
+}
+
+/// Moves the upgrade authority of `program` to an escrow that only holds this program, told
+/// apart from other handovers between the same authorities by `nonce`
+pub fn propose_scoped_ix(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    program: &Pubkey,
+    nonce: u64,
+    memo: Option<HandoverMemo>,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_escrow::id(),
+        accounts:   escrow_propose_scoped_accounts(
+            current_authority,
+            new_authority,
+            program,
+            nonce,
+        )
+        .to_account_metas(None),
+        data:       program_authority_escrow::instruction::ProposeScoped { nonce, memo }.data(),
+    }
+}
+
+pub fn revert_scoped_ix(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    program: &Pubkey,
+    nonce: u64,
+    memo: Option<HandoverMemo>,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_escrow::id(),
+        accounts:   escrow_propose_scoped_accounts(
+            current_authority,
+            new_authority,
+            program,
+            nonce,
+        )
+        .to_account_metas(None),
+        data:       program_authority_escrow::instruction::RevertScoped { nonce, memo }.data(),
+    }
+}
+
+pub fn accept_scoped_ix(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    program: &Pubkey,
+    nonce: u64,
+    memo: Option<HandoverMemo>,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_escrow::id(),
+        accounts:   program_authority_escrow::accounts::AcceptScoped {
+            current_authority:     *current_authority,
+            new_authority:         *new_authority,
+            escrow_authority:      scoped_escrow_authority(
+                current_authority,
+                new_authority,
+                program,
+                nonce,
+            ),
+            program_account:       *program,
+            program_data:          program_data_address(program),
+            bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+        }
+        .to_account_metas(None),
+        data:       program_authority_escrow::instruction::AcceptScoped { nonce, memo }.data(),
+    }
+}
+
+/// Upgrades `program` from `buffer` while it is in escrow, the lamports of the buffer go to
+/// `spill`
+pub fn upgrade_ix(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    program: &Pubkey,
+    buffer: &Pubkey,
+    spill: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_escrow::id(),
+        accounts:   program_authority_escrow::accounts::Upgrade {
+            current_authority:     *current_authority,
+            new_authority:         *new_authority,
+            escrow_authority:      escrow_authority(current_authority, new_authority),
+            program_account:       *program,
+            program_data:          program_data_address(program),
+            buffer:                *buffer,
+            spill:                 *spill,
+            rent:                  sysvar::rent::id(),
+            clock:                 sysvar::clock::id(),
+            bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+        }
+        .to_account_metas(None),
+        data:       program_authority_escrow::instruction::Upgrade.data(),
+    }
+}
+
+/// Moves the upgrade authority of `program` to the immutable authority, until
+/// `confirm_authority` confirms or `current_authority` reverts
+pub fn propose_immutable_ix(
+    current_authority: &Pubkey,
+    confirm_authority: &Pubkey,
+    program: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_escrow::id(),
+        accounts:   escrow_propose_immutable_accounts(
+            current_authority,
+            confirm_authority,
+            program,
+        )
+        .to_account_metas(None),
+        data:       program_authority_escrow::instruction::ProposeImmutable.data(),
+    }
+}
+
+pub fn revert_immutable_ix(
+    current_authority: &Pubkey,
+    confirm_authority: &Pubkey,
+    program: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_escrow::id(),
+        accounts:   escrow_propose_immutable_accounts(
+            current_authority,
+            confirm_authority,
+            program,
+        )
+        .to_account_metas(None),
+        data:       program_authority_escrow::instruction::RevertImmutable.data(),
+    }
+}
+
+/// Makes `program` immutable, `confirm_authority` has to sign
+pub fn confirm_immutable_ix(
+    current_authority: &Pubkey,
+    confirm_authority: &Pubkey,
+    program: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_escrow::id(),
+        accounts:   program_authority_escrow::accounts::ConfirmImmutable {
+            current_authority:     *current_authority,
+            confirm_authority:     *confirm_authority,
+            immutable_authority:   immutable_authority(current_authority, confirm_authority),
+            program_account:       *program,
+            program_data:          program_data_address(program),
+            bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+        }
+        .to_account_metas(None),
+        data:       program_authority_escrow::instruction::ConfirmImmutable.data(),
+    }
+}
+
+/// Fails the transaction unless the upgrade authority of `program` is `expected_authority`,
+/// `None` expecting an immutable program
+pub fn assert_authority_ix(program: &Pubkey, expected_authority: Option<Pubkey>) -> Instruction {
+    Instruction {
+        program_id: program_authority_escrow::id(),
+        accounts:   program_authority_escrow::accounts::AssertAuthority {
+            program_account:       *program,
+            program_data:          program_data_address(program),
+            bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+        }
+        .to_account_metas(None),
+        data:       program_authority_escrow::instruction::AssertAuthority { expected_authority }
+            .data(),
+    }
+}
+
+/// Extends the program data account of `program` while it is in escrow, `payer` pays the rent
+pub fn escrow_extend_program_ix(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    payer: &Pubkey,
+    program: &Pubkey,
+    additional_bytes: u32,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_escrow::id(),
+        accounts:   program_authority_escrow::accounts::ExtendProgram {
+            current_authority:     *current_authority,
+            new_authority:         *new_authority,
+            escrow_authority:      escrow_authority(current_authority, new_authority),
+            payer:                 *payer,
+            program_account:       *program,
+            program_data:          program_data_address(program),
+            system_program:        system_program::id(),
+            bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+        }
+        .to_account_metas(None),
+        data:       program_authority_escrow::instruction::ExtendProgram { additional_bytes }
+            .data(),
+    }
+}
+
+/// Locks the upgrade authority of `program` in the timelock until `timestamp`, through
+/// `commit_with_memo` when given a memo
+pub fn commit_ix(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    program: &Pubkey,
+    timestamp: i64,
+    memo: Option<HandoverMemo>,
+) -> Instruction {
+    match memo {
+        Some(memo) => {
+            commit_with_memo_ix(current_authority, new_authority, program, timestamp, memo)
+        }
+        None => Instruction {
+            program_id: program_authority_timelock::id(),
+            accounts:   timelock_commit_accounts(
+                current_authority,
+                new_authority,
+                program,
+                timestamp,
+            )
+            .to_account_metas(None),
+            data:       program_authority_timelock::instruction::Commit { timestamp }.data(),
+        },
+    }
+}
+
+pub fn commit_with_memo_ix(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    program: &Pubkey,
+    timestamp: i64,
+    memo: HandoverMemo,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_timelock::id(),
+        accounts:   timelock_commit_accounts(current_authority, new_authority, program, timestamp)
+            .to_account_metas(None),
+        data:       program_authority_timelock::instruction::CommitWithMemo { timestamp, memo }
+            .data(),
+    }
+}
+
+/// Same as `commit_ix`, but the program rejects new authorities that are neither wallets nor
+/// owned by an allowlisted program
+pub fn commit_strict_ix(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    program: &Pubkey,
+    timestamp: i64,
+    memo: Option<HandoverMemo>,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_timelock::id(),
+        accounts:   timelock_commit_accounts(current_authority, new_authority, program, timestamp)
+            .to_account_metas(None),
+        data:       program_authority_timelock::instruction::CommitStrict { timestamp, memo }
+            .data(),
+    }
+}
+
+/// Same as `commit_ix`, along with a commitment record whose rent `payer` gets back on close
+pub fn commit_with_record_ix(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    payer: &Pubkey,
+    program: &Pubkey,
+    timestamp: i64,
+    memo: Option<HandoverMemo>,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_timelock::id(),
+        accounts:   program_authority_timelock::accounts::CommitWithRecord {
+            current_authority:     *current_authority,
+            new_authority:         *new_authority,
+            escrow_authority:      timelock_authority(new_authority, timestamp),
+            commitment_record:     commitment_record_address(new_authority, timestamp, program),
+            payer:                 *payer,
+            program_account:       *program,
+            program_data:          program_data_address(program),
+            system_program:        system_program::id(),
+            bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+        }
+        .to_account_metas(None),
+        data:       program_authority_timelock::instruction::CommitWithRecord { timestamp, memo }
+            .data(),
+    }
+}
+
+/// Closes the commitment record once the program left the timelock, the rent goes back to
+/// `rent_payer`
+pub fn close_commitment_record_ix(
+    new_authority: &Pubkey,
+    rent_payer: &Pubkey,
+    program: &Pubkey,
+    timestamp: i64,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_timelock::id(),
+        accounts:   program_authority_timelock::accounts::CloseCommitmentRecord {
+            commitment_record:     commitment_record_address(new_authority, timestamp, program),
+            rent_payer:            *rent_payer,
+            escrow_authority:      timelock_authority(new_authority, timestamp),
+            program_account:       *program,
+            program_data:          program_data_address(program),
+            bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+        }
+        .to_account_metas(None),
+        data:       program_authority_timelock::instruction::CloseCommitmentRecord.data(),
+    }
+}
+
+/// Gives the upgrade authority of `program` to `new_authority` once `timestamp` has passed,
+/// through `transfer_with_memo` when given a memo
+pub fn transfer_ix(
+    new_authority: &Pubkey,
+    program: &Pubkey,
+    timestamp: i64,
+    memo: Option<HandoverMemo>,
+) -> Instruction {
+    match memo {
+        Some(memo) => transfer_with_memo_ix(new_authority, program, timestamp, memo),
+        None => Instruction {
+            program_id: program_authority_timelock::id(),
+            accounts:   timelock_transfer_accounts(new_authority, program, timestamp)
+                .to_account_metas(None),
+            data:       program_authority_timelock::instruction::Transfer { timestamp }.data(),
+        },
+    }
+}
+
+pub fn transfer_with_memo_ix(
+    new_authority: &Pubkey,
+    program: &Pubkey,
+    timestamp: i64,
+    memo: HandoverMemo,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_timelock::id(),
+        accounts:   timelock_transfer_accounts(new_authority, program, timestamp)
+            .to_account_metas(None),
+        data:       program_authority_timelock::instruction::TransferWithMemo { timestamp, memo }
+            .data(),
+    }
+}
+
+/// Same as `transfer_ix`, but `new_authority` has to sign
+pub fn transfer_checked_ix(
+    new_authority: &Pubkey,
+    program: &Pubkey,
+    timestamp: i64,
+    memo: Option<HandoverMemo>,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_timelock::id(),
+        accounts:   program_authority_timelock::accounts::TransferChecked {
+            new_authority:         *new_authority,
+            escrow_authority:      timelock_authority(new_authority, timestamp),
+            program_account:       *program,
+            program_data:          program_data_address(program),
+            bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+        }
+        .to_account_metas(None),
+        data:       program_authority_timelock::instruction::TransferChecked { timestamp, memo }
+            .data(),
+    }
+}
+
+/// Extends the program data account of `program` while it is timelocked, `payer` pays the rent
+pub fn timelock_extend_program_ix(
+    new_authority: &Pubkey,
+    payer: &Pubkey,
+    program: &Pubkey,
+    timestamp: i64,
+    additional_bytes: u32,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_timelock::id(),
+        accounts:   program_authority_timelock::accounts::ExtendProgram {
+            new_authority:         *new_authority,
+            escrow_authority:      timelock_authority(new_authority, timestamp),
+            payer:                 *payer,
+            program_account:       *program,
+            program_data:          program_data_address(program),
+            system_program:        system_program::id(),
+            bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+        }
+        .to_account_metas(None),
+        data:       program_authority_timelock::instruction::ExtendProgram {
+            timestamp,
+            additional_bytes,
+        }
+        .data(),
+    }
+}
+
+/// Returns the `AuthorityStatus` of `program` as return data, matching its upgrade authority
+/// against the escrow and timelock addresses of the candidates. Past a few candidates, the
+/// transaction has to request `STATUS_COMPUTE_UNIT_LIMIT` compute units
+pub fn status_ix(
+    program: &Pubkey,
+    candidates: Vec<Pubkey>,
+    timestamps: Vec<i64>,
+    nonces: Vec<u64>,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_escrow::id(),
+        accounts:   program_authority_escrow::accounts::Status {
+            program_account:       *program,
+            program_data:          program_data_address(program),
+            bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+        }
+        .to_account_metas(None),
+        data:       program_authority_escrow::instruction::Status {
+            candidates,
+            timestamps,
+            nonces,
+        }
+        .data(),
+    }
+}
+
+/// Same as `status_ix`, answered by the timelock
+pub fn timelock_status_ix(
+    program: &Pubkey,
+    candidates: Vec<Pubkey>,
+    timestamps: Vec<i64>,
+    nonces: Vec<u64>,
+) -> Instruction {
+    Instruction {
+        program_id: program_authority_timelock::id(),
+        accounts:   program_authority_timelock::accounts::Status {
+            program_account:       *program,
+            program_data:          program_data_address(program),
+            bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+        }
+        .to_account_metas(None),
+        data:       program_authority_timelock::instruction::Status {
+            candidates,
+            timestamps,
+            nonces,
+        }
+        .data(),
+    }
+}
+
+/// `propose`, `revert` and their memo and strict variants share the same accounts
+fn escrow_propose_accounts(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    program: &Pubkey,
+) -> program_authority_escrow::accounts::Propose {
+    program_authority_escrow::accounts::Propose {
+        current_authority:     *current_authority,
+        new_authority:         *new_authority,
+        escrow_authority:      escrow_authority(current_authority, new_authority),
+        program_account:       *program,
+        program_data:          program_data_address(program),
+        bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+    }
+}
+
+/// `accept`, `accept_with_memo` and `accept_checked` share the same accounts
+fn escrow_accept_accounts(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    program: &Pubkey,
+) -> program_authority_escrow::accounts::Accept {
+    program_authority_escrow::accounts::Accept {
+        current_authority:     *current_authority,
+        new_authority:         *new_authority,
+        escrow_authority:      escrow_authority(current_authority, new_authority),
+        program_account:       *program,
+        program_data:          program_data_address(program),
+        bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+    }
+}
+
+/// `propose_scoped` and `revert_scoped` share the same accounts
+fn escrow_propose_scoped_accounts(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    program: &Pubkey,
+    nonce: u64,
+) -> program_authority_escrow::accounts::ProposeScoped {
+    program_authority_escrow::accounts::ProposeScoped {
+        current_authority:     *current_authority,
+        new_authority:         *new_authority,
+        escrow_authority:      scoped_escrow_authority(
+            current_authority,
+            new_authority,
+            program,
+            nonce,
+        ),
+        program_account:       *program,
+        program_data:          program_data_address(program),
+        bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+    }
+}
+
+/// `propose_immutable` and `revert_immutable` share the same accounts
+fn escrow_propose_immutable_accounts(
+    current_authority: &Pubkey,
+    confirm_authority: &Pubkey,
+    program: &Pubkey,
+) -> program_authority_escrow::accounts::ProposeImmutable {
+    program_authority_escrow::accounts::ProposeImmutable {
+        current_authority:     *current_authority,
+        confirm_authority:     *confirm_authority,
+        immutable_authority:   immutable_authority(current_authority, confirm_authority),
+        program_account:       *program,
+        program_data:          program_data_address(program),
+        bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+    }
+}
+
+/// `commit`, `commit_with_memo` and `commit_strict` share the same accounts
+fn timelock_commit_accounts(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    program: &Pubkey,
+    timestamp: i64,
+) -> program_authority_timelock::accounts::Commit {
+    program_authority_timelock::accounts::Commit {
+        current_authority:     *current_authority,
+        new_authority:         *new_authority,
+        escrow_authority:      timelock_authority(new_authority, timestamp),
+        program_account:       *program,
+        program_data:          program_data_address(program),
+        bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+    }
+}
+
+/// `transfer` and `transfer_with_memo` share the same accounts
+fn timelock_transfer_accounts(
+    new_authority: &Pubkey,
+    program: &Pubkey,
+    timestamp: i64,
+) -> program_authority_timelock::accounts::Transfer {
+    program_authority_timelock::accounts::Transfer {
+        new_authority:         *new_authority,
+        escrow_authority:      timelock_authority(new_authority, timestamp),
+        program_account:       *program,
+        program_data:          program_data_address(program),
+        bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+    }
+}
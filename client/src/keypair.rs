@@ -0,0 +1,24 @@
+use {
+    anyhow::{
+        anyhow,
+        Result,
+    },
+    solana_sdk::signature::{
+        read_keypair_file,
+        Keypair,
+    },
+    std::path::{
+        Path,
+        PathBuf,
+    },
+};
+
+/// Reads a keypair file, expanding a leading `~` to the home directory
+pub fn read_keypair(path: &Path) -> Result<Keypair> {
+    let path = match (path.strip_prefix("~"), std::env::var_os("HOME")) {
+        (Ok(relative), Some(home)) => PathBuf::from(home).join(relative),
+        _ => path.to_path_buf(),
+    };
+    read_keypair_file(&path)
+        .map_err(|err| anyhow!("Failed to read keypair {}: {}", path.display(), err))
+}
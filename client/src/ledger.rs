@@ -0,0 +1,241 @@
+use {
+    solana_sdk::{
+        pubkey::Pubkey,
+        signature::{
+            Keypair,
+            Signature,
+        },
+        signer::{
+            Signer,
+            SignerError,
+        },
+    },
+    std::cell::RefCell,
+};
+
+const INS_GET_PUBKEY: u8 = 0x05;
+const INS_SIGN_MESSAGE: u8 = 0x06;
+
+const P1_NON_CONFIRM: u8 = 0x00;
+const P1_CONFIRM: u8 = 0x01;
+
+const P2_EXTEND: u8 = 0x01;
+const P2_MORE: u8 = 0x02;
+
+const MAX_PAYLOAD: usize = 255;
+
+const LEDGER_CLA: u8 = 0xe0;
+
+const SW_OK: u16 = 0x9000;
+const SW_USER_REFUSED: u16 = 0x6985;
+
+const BIP32_HARDENED_BIT: u32 = 1 << 31;
+
+/// Carries APDUs to a Ledger device, over USB HID for a real one
+pub trait LedgerTransport {
+    /// Sends `CLA INS P1 P2 Lc data` and returns the response, status word included
+    fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, SignerError>;
+}
+
+/// Serialized BIP44 path `44'/501'/account'/change'`, as the Solana app expects it. Like the
+/// TypeScript wallet, `change` is only used along with an `account`
+pub fn derivation_path(account: Option<u32>, change: Option<u32>) -> Vec<u8> {
+    let mut indexes = vec![44, 501];
+    if let Some(account) = account {
+        indexes.push(account);
+        indexes.extend(change);
+    }
+
+    let mut derivation_path = vec![indexes.len() as u8];
+    for index in indexes {
+        derivation_path.extend((index | BIP32_HARDENED_BIT).to_be_bytes());
+    }
+    derivation_path
+}
+
+/// Sends `data` in chunks of `MAX_PAYLOAD` bytes, every chunk but the last with `P2_MORE` and every
+/// chunk but the first with `P2_EXTEND`. Returns the response to the last chunk without its status
+/// word
+fn send(
+    transport: &impl LedgerTransport,
+    instruction: u8,
+    p1: u8,
+    data: &[u8],
+) -> Result<Vec<u8>, SignerError> {
+    let mut p2 = 0;
+    let mut offset = 0;
+
+    while data.len() - offset > MAX_PAYLOAD {
+        let response = exchange(
+            transport,
+            instruction,
+            p1,
+            p2 | P2_MORE,
+            &data[offset..offset + MAX_PAYLOAD],
+        )?;
+        if !response.is_empty() {
+            return Err(SignerError::Protocol(
+                "Unexpected data in the response to a partial message".to_string(),
+            ));
+        }
+
+        p2 |= P2_EXTEND;
+        offset += MAX_PAYLOAD;
+    }
+
+    exchange(transport, instruction, p1, p2, &data[offset..])
+}
+
+fn exchange(
+    transport: &impl LedgerTransport,
+    instruction: u8,
+    p1: u8,
+    p2: u8,
+    payload: &[u8],
+) -> Result<Vec<u8>, SignerError> {
+    let mut apdu = vec![LEDGER_CLA, instruction, p1, p2, payload.len() as u8];
+    apdu.extend_from_slice(payload);
+
+    let mut response = transport.exchange(&apdu)?;
+    if response.len() < 2 {
+        return Err(SignerError::Protocol(
+            "Response is missing its status word".to_string(),
+        ));
+    }
+    let status = response.split_off(response.len() - 2);
+    match u16::from_be_bytes([status[0], status[1]]) {
+        SW_OK => Ok(response),
+        SW_USER_REFUSED => Err(SignerError::UserCancel(
+            "Rejected on the Ledger".to_string(),
+        )),
+        status => Err(SignerError::Protocol(format!(
+            "Ledger returned status {status:#06x}"
+        ))),
+    }
+}
+
+/// Signs with the key of the Solana app at a BIP44 derivation path
+pub struct LedgerSigner<T: LedgerTransport> {
+    transport:       T,
+    derivation_path: Vec<u8>,
+    pubkey:          Pubkey,
+}
+
+impl<T: LedgerTransport> LedgerSigner<T> {
+    /// Reads the public key at `44'/501'/account'/change'` from the device
+    pub fn new(
+        transport: T,
+        account: Option<u32>,
+        change: Option<u32>,
+    ) -> Result<Self, SignerError> {
+        let derivation_path = derivation_path(account, change);
+        let pubkey = send(&transport, INS_GET_PUBKEY, P1_NON_CONFIRM, &derivation_path)?;
+        let pubkey = Pubkey::try_from(pubkey.as_slice())
+            .map_err(|_| SignerError::Protocol("Invalid public key length".to_string()))?;
+
+        Ok(LedgerSigner {
+            transport,
+            derivation_path,
+            pubkey,
+        })
+    }
+
+    pub fn get_transport(&self) -> &T {
+        &self.transport
+    }
+}
+
+impl<T: LedgerTransport> Signer for LedgerSigner<T> {
+    fn try_pubkey(&self) -> Result<Pubkey, SignerError> {
+        Ok(self.pubkey)
+    }
+
+    /// Asks for a confirmation on the device, the payload is the number of derivation paths, the
+    /// path and the serialized message
+    fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        let mut data = vec![1];
+        data.extend_from_slice(&self.derivation_path);
+        data.extend_from_slice(message);
+
+        let signature = send(&self.transport, INS_SIGN_MESSAGE, P1_CONFIRM, &data)?;
+        if signature.len() != 64 {
+            return Err(SignerError::Protocol(
+                "Invalid signature length".to_string(),
+            ));
+        }
+        Ok(Signature::new(&signature))
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+}
+
+/// In-memory stand-in for a device : records every APDU, reassembles chunked payloads and answers
+/// with the public key and signatures of `keypair`, whatever the derivation path
+pub struct MockTransport {
+    keypair: Keypair,
+    refuse:  bool,
+    apdus:   RefCell<Vec<Vec<u8>>>,
+    pending: RefCell<Vec<u8>>,
+}
+
+impl MockTransport {
+    pub fn new(keypair: Keypair) -> Self {
+        MockTransport {
+            keypair,
+            refuse: false,
+            apdus: RefCell::new(vec![]),
+            pending: RefCell::new(vec![]),
+        }
+    }
+
+    /// Behaves like a user rejecting every signature on the device
+    pub fn refusing(keypair: Keypair) -> Self {
+        MockTransport {
+            refuse: true,
+            ..MockTransport::new(keypair)
+        }
+    }
+
+    pub fn get_apdus(&self) -> Vec<Vec<u8>> {
+        self.apdus.borrow().clone()
+    }
+}
+
+impl LedgerTransport for MockTransport {
+    fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, SignerError> {
+        self.apdus.borrow_mut().push(apdu.to_vec());
+
+        let (header, payload) = apdu.split_at(5);
+        if header[0] != LEDGER_CLA || header[4] as usize != payload.len() {
+            return Ok(0x6e00u16.to_be_bytes().to_vec());
+        }
+        let (instruction, p1, p2) = (header[1], header[2], header[3]);
+
+        let mut pending = self.pending.borrow_mut();
+        if p2 & P2_EXTEND == 0 {
+            pending.clear();
+        }
+        pending.extend_from_slice(payload);
+        if p2 & P2_MORE != 0 {
+            return Ok(SW_OK.to_be_bytes().to_vec());
+        }
+
+        let data = std::mem::take(&mut *pending);
+        let mut response = match (instruction, p1) {
+            (INS_GET_PUBKEY, P1_NON_CONFIRM) => self.keypair.pubkey().to_bytes().to_vec(),
+            (INS_SIGN_MESSAGE, P1_CONFIRM) if self.refuse => {
+                return Ok(SW_USER_REFUSED.to_be_bytes().to_vec())
+            }
+            (INS_SIGN_MESSAGE, P1_CONFIRM) => {
+                // Skips the number of paths, the path length and the path itself
+                let message = &data[2 + 4 * data[1] as usize..];
+                self.keypair.sign_message(message).as_ref().to_vec()
+            }
+            _ => return Ok(0x6d00u16.to_be_bytes().to_vec()),
+        };
+        response.extend(SW_OK.to_be_bytes());
+        Ok(response)
+    }
+}
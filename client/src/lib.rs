@@ -0,0 +1,31 @@
+#![deny(warnings)]
+
+//! Off-chain builders for the escrow and timelock instructions, along with the addresses and
+//! account layouts they need, and the cluster and keypair helpers shared by the CLI and the crank.
+
+pub mod cluster;
+pub mod history;
+pub mod instruction;
+pub mod keypair;
+pub mod ledger;
+pub mod offline;
+pub mod pda;
+pub mod program_data;
+pub mod pubkey_serde;
+pub mod status;
+
+pub use {
+    cluster::*,
+    history::*,
+    instruction::*,
+    keypair::*,
+    ledger::*,
+    offline::*,
+    pda::*,
+    program_data::*,
+    pubkey_serde::*,
+    status::*,
+};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,89 @@
+use {
+    anchor_lang::{
+        prelude::Pubkey,
+        solana_program::{
+            instruction::Instruction,
+            system_instruction,
+        },
+    },
+    serde::{
+        de::DeserializeOwned,
+        Serialize,
+    },
+    std::{
+        fmt,
+        str::FromStr,
+    },
+};
+
+/// Text encoding of serialized instructions, messages and transactions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    Base58,
+    Base64,
+}
+
+impl FromStr for Encoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "base58" => Ok(Encoding::Base58),
+            "base64" => Ok(Encoding::Base64),
+            _ => Err(format!("Unknown encoding {s}, expected base58 or base64")),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum DecodeError {
+    Base58(bs58::decode::Error),
+    Base64(base64::DecodeError),
+    Bincode(bincode::Error),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::Base58(err) => write!(f, "Invalid base58: {err}"),
+            DecodeError::Base64(err) => write!(f, "Invalid base64: {err}"),
+            DecodeError::Bincode(err) => write!(f, "Invalid serialized data: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {
+}
+
+/// Serializes an `Instruction`, `Message` or `Transaction` with bincode, like the wire format
+pub fn encode<T: Serialize>(value: &T, encoding: Encoding) -> String {
+    let bytes = bincode::serialize(value).expect("Should always serialize");
+    match encoding {
+        Encoding::Base58 => bs58::encode(bytes).into_string(),
+        Encoding::Base64 => base64::encode(bytes),
+    }
+}
+
+pub fn decode<T: DeserializeOwned>(encoded: &str, encoding: Encoding) -> Result<T, DecodeError> {
+    let bytes = match encoding {
+        Encoding::Base58 => bs58::decode(encoded.trim())
+            .into_vec()
+            .map_err(DecodeError::Base58)?,
+        Encoding::Base64 => base64::decode(encoded.trim()).map_err(DecodeError::Base64)?,
+    };
+    bincode::deserialize(&bytes).map_err(DecodeError::Bincode)
+}
+
+/// Prepends the nonce advance so that the transaction can use the durable nonce as its blockhash
+pub fn with_durable_nonce(
+    nonce_account: &Pubkey,
+    nonce_authority: &Pubkey,
+    instructions: &[Instruction],
+) -> Vec<Instruction> {
+    let mut nonced_instructions = vec![system_instruction::advance_nonce_account(
+        nonce_account,
+        nonce_authority,
+    )];
+    nonced_instructions.extend_from_slice(instructions);
+    nonced_instructions
+}
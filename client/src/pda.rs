@@ -0,0 +1,93 @@
+use {
+    anchor_lang::{
+        prelude::Pubkey,
+        solana_program::bpf_loader_upgradeable,
+    },
+    program_authority_escrow::{
+        IMMUTABLE_SEED,
+        PROPOSAL_RECORD_SEED,
+    },
+    program_authority_timelock::COMMITMENT_RECORD_SEED,
+};
+
+pub fn program_data_address(program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[program.as_ref()], &bpf_loader_upgradeable::id()).0
+}
+
+pub fn escrow_authority(current_authority: &Pubkey, new_authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[current_authority.as_ref(), new_authority.as_ref()],
+        &program_authority_escrow::id(),
+    )
+    .0
+}
+
+pub fn scoped_escrow_authority(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    program: &Pubkey,
+    nonce: u64,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            current_authority.as_ref(),
+            new_authority.as_ref(),
+            program.as_ref(),
+            nonce.to_be_bytes().as_ref(),
+        ],
+        &program_authority_escrow::id(),
+    )
+    .0
+}
+
+pub fn immutable_authority(current_authority: &Pubkey, confirm_authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            IMMUTABLE_SEED,
+            current_authority.as_ref(),
+            confirm_authority.as_ref(),
+        ],
+        &program_authority_escrow::id(),
+    )
+    .0
+}
+
+pub fn proposal_record_address(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    program: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            PROPOSAL_RECORD_SEED,
+            escrow_authority(current_authority, new_authority).as_ref(),
+            program.as_ref(),
+        ],
+        &program_authority_escrow::id(),
+    )
+    .0
+}
+
+pub fn timelock_authority(new_authority: &Pubkey, timestamp: i64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[new_authority.as_ref(), timestamp.to_be_bytes().as_ref()],
+        &program_authority_timelock::id(),
+    )
+    .0
+}
+
+pub fn commitment_record_address(
+    new_authority: &Pubkey,
+    timestamp: i64,
+    program: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            COMMITMENT_RECORD_SEED,
+            timelock_authority(new_authority, timestamp).as_ref(),
+            program.as_ref(),
+        ],
+        &program_authority_timelock::id(),
+    )
+    .0
+}
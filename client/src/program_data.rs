@@ -0,0 +1,30 @@
+use anchor_lang::{
+    error::ErrorCode,
+    prelude::{
+        Pubkey,
+        Result,
+        UpgradeableLoaderState,
+    },
+    AccountDeserialize,
+    ProgramData,
+};
+
+/// Decodes the program data account of an upgradeable program
+pub fn decode_program_data(data: &[u8]) -> Result<ProgramData> {
+    ProgramData::try_deserialize(&mut &data[..])
+}
+
+/// Current upgrade authority of a program data account, `None` once the program is immutable
+pub fn decode_upgrade_authority(data: &[u8]) -> Result<Option<Pubkey>> {
+    Ok(decode_program_data(data)?.upgrade_authority_address)
+}
+
+/// Decodes an upgradeable program account and returns the address of its program data
+pub fn decode_program(data: &[u8]) -> Result<Pubkey> {
+    match UpgradeableLoaderState::try_deserialize(&mut &data[..])? {
+        UpgradeableLoaderState::Program {
+            programdata_address,
+        } => Ok(programdata_address),
+        _ => Err(ErrorCode::AccountDidNotDeserialize.into()),
+    }
+}
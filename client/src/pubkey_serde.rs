@@ -0,0 +1,40 @@
+use {
+    anchor_lang::prelude::Pubkey,
+    serde::{
+        de::Error,
+        Deserialize,
+        Deserializer,
+        Serializer,
+    },
+    std::str::FromStr,
+};
+
+/// Public keys are written as base58 strings in the plan, watch list and monitor files
+fn parse_pubkey<E: Error>(key: &str) -> Result<Pubkey, E> {
+    Pubkey::from_str(key).map_err(|_| E::custom(format!("Invalid public key {key}")))
+}
+
+pub fn serialize_pubkey<S: Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&pubkey.to_string())
+}
+
+pub fn deserialize_pubkey<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+    parse_pubkey(&String::deserialize(deserializer)?)
+}
+
+pub fn deserialize_optional_pubkey<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Pubkey>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|key| parse_pubkey(&key))
+        .transpose()
+}
+
+pub fn deserialize_pubkeys<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Pubkey>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|key| parse_pubkey(key))
+        .collect()
+}
@@ -0,0 +1,29 @@
+use {
+    anchor_lang::prelude::Pubkey,
+    program_authority_common::{
+        find_authority_status,
+        AuthorityStatus,
+    },
+};
+
+/// Off-chain version of the escrow's `status` instruction, without its caps on the candidates :
+/// matches the upgrade authority of `program` against the escrow addresses of every pair of
+/// candidates, plain, scoped to every nonce or pending immutability, and the timelock addresses of
+/// every candidate and timestamp. `now` decides whether a timelock has matured.
+pub fn inspect_authority(
+    program: &Pubkey,
+    upgrade_authority: Option<Pubkey>,
+    candidates: &[Pubkey],
+    timestamps: &[i64],
+    nonces: &[u64],
+    now: i64,
+) -> AuthorityStatus {
+    find_authority_status(
+        program,
+        upgrade_authority,
+        candidates,
+        timestamps,
+        nonces,
+        now,
+    )
+}
@@ -0,0 +1,892 @@
+use {
+    crate::{
+        accept_checked_ix,
+        accept_ix,
+        accept_scoped_ix,
+        close_commitment_record_ix,
+        close_proposal_record_ix,
+        commit_ix,
+        commit_strict_ix,
+        commit_with_record_ix,
+        commitment_record_address,
+        confirm_immutable_ix,
+        decode,
+        decode_handover,
+        decode_program,
+        decode_program_data,
+        decode_transaction,
+        decode_upgrade_authority,
+        derivation_path,
+        encode,
+        escrow_authority,
+        immutable_authority,
+        inspect_authority,
+        program_data_address,
+        proposal_record_address,
+        propose_immutable_ix,
+        propose_ix,
+        propose_scoped_ix,
+        propose_strict_ix,
+        propose_with_record_ix,
+        revert_ix,
+        scoped_escrow_authority,
+        status_ix,
+        timelock_authority,
+        transfer_checked_ix,
+        transfer_ix,
+        with_durable_nonce,
+        DecodedHandover,
+        Encoding,
+        LedgerSigner,
+        MockTransport,
+    },
+    anchor_lang::{
+        prelude::{
+            Pubkey,
+            UpgradeableLoaderState,
+        },
+        solana_program::{
+            bpf_loader_upgradeable,
+            hash::{
+                hash,
+                Hash,
+            },
+            instruction::{
+                AccountMeta,
+                Instruction,
+            },
+            message::Message,
+            system_instruction,
+            system_program,
+        },
+        InstructionData,
+        ToAccountMetas,
+    },
+    program_authority_escrow::{
+        AuthorityStatus,
+        HandoverMemo,
+    },
+    solana_sdk::{
+        signature::Keypair,
+        signer::{
+            Signer,
+            SignerError,
+        },
+    },
+    std::str::FromStr,
+};
+
+fn sighash(name: &str) -> Vec<u8> {
+    hash(format!("global:{name}").as_bytes()).to_bytes()[..8].to_vec()
+}
+
+#[test]
+fn test_propose_and_revert_ix() {
+    let current_authority = Pubkey::new_unique();
+    let new_authority = Pubkey::new_unique();
+    let program = Pubkey::new_unique();
+
+    let expected_accounts = vec![
+        AccountMeta::new_readonly(current_authority, true),
+        AccountMeta::new_readonly(new_authority, false),
+        AccountMeta::new_readonly(escrow_authority(&current_authority, &new_authority), false),
+        AccountMeta::new_readonly(program, false),
+        AccountMeta::new(program_data_address(&program), false),
+        AccountMeta::new_readonly(bpf_loader_upgradeable::id(), false),
+    ];
+
+    let instruction = propose_ix(&current_authority, &new_authority, &program, None);
+    assert_eq!(instruction.program_id, program_authority_escrow::id());
+    assert_eq!(instruction.accounts, expected_accounts);
+    // Without a memo, the data is the one of the first deployed `propose`
+    assert_eq!(instruction.data, sighash("propose"));
+
+    let instruction = revert_ix(
+        &current_authority,
+        &new_authority,
+        &program,
+        Some(HandoverMemo::Hash([7; 32])),
+    );
+    assert_eq!(instruction.program_id, program_authority_escrow::id());
+    assert_eq!(instruction.accounts, expected_accounts);
+    assert_eq!(
+        instruction.data,
+        [sighash("revert_with_memo"), vec![1], vec![7; 32]].concat()
+    );
+}
+
+#[test]
+fn test_accept_ix() {
+    let current_authority = Pubkey::new_unique();
+    let new_authority = Pubkey::new_unique();
+    let program = Pubkey::new_unique();
+
+    let instruction = accept_ix(&current_authority, &new_authority, &program, None);
+    assert_eq!(instruction.program_id, program_authority_escrow::id());
+    assert_eq!(
+        instruction.accounts,
+        vec![
+            AccountMeta::new_readonly(current_authority, false),
+            AccountMeta::new_readonly(new_authority, true),
+            AccountMeta::new_readonly(escrow_authority(&current_authority, &new_authority), false),
+            AccountMeta::new_readonly(program, false),
+            AccountMeta::new(program_data_address(&program), false),
+            AccountMeta::new_readonly(bpf_loader_upgradeable::id(), false),
+        ]
+    );
+    assert_eq!(instruction.data, sighash("accept"));
+}
+
+#[test]
+fn test_commit_and_transfer_ix() {
+    let current_authority = Pubkey::new_unique();
+    let new_authority = Pubkey::new_unique();
+    let program = Pubkey::new_unique();
+    let timestamp: i64 = 1700000000;
+
+    let instruction = commit_ix(
+        &current_authority,
+        &new_authority,
+        &program,
+        timestamp,
+        None,
+    );
+    assert_eq!(instruction.program_id, program_authority_timelock::id());
+    assert_eq!(
+        instruction.accounts,
+        vec![
+            AccountMeta::new_readonly(current_authority, true),
+            AccountMeta::new_readonly(new_authority, false),
+            AccountMeta::new_readonly(timelock_authority(&new_authority, timestamp), false),
+            AccountMeta::new_readonly(program, false),
+            AccountMeta::new(program_data_address(&program), false),
+            AccountMeta::new_readonly(bpf_loader_upgradeable::id(), false),
+        ]
+    );
+    assert_eq!(
+        instruction.data,
+        [sighash("commit"), timestamp.to_le_bytes().to_vec()].concat()
+    );
+
+    let instruction = transfer_ix(&new_authority, &program, timestamp, None);
+    assert_eq!(instruction.program_id, program_authority_timelock::id());
+    assert_eq!(
+        instruction.accounts,
+        vec![
+            AccountMeta::new_readonly(new_authority, false),
+            AccountMeta::new_readonly(timelock_authority(&new_authority, timestamp), false),
+            AccountMeta::new_readonly(program, false),
+            AccountMeta::new(program_data_address(&program), false),
+            AccountMeta::new_readonly(bpf_loader_upgradeable::id(), false),
+        ]
+    );
+    assert_eq!(
+        instruction.data,
+        [sighash("transfer"), timestamp.to_le_bytes().to_vec()].concat()
+    );
+
+    let instruction = transfer_ix(
+        &new_authority,
+        &program,
+        timestamp,
+        Some(HandoverMemo::Reason("Unlocked".to_string())),
+    );
+    assert_eq!(
+        instruction.data,
+        [
+            sighash("transfer_with_memo"),
+            timestamp.to_le_bytes().to_vec(),
+            vec![0, 8, 0, 0, 0],
+            b"Unlocked".to_vec()
+        ]
+        .concat()
+    );
+}
+
+#[test]
+fn test_status_ix() {
+    let program = Pubkey::new_unique();
+    let candidate = Pubkey::new_unique();
+
+    let instruction = status_ix(&program, vec![candidate], vec![1700000000], vec![7]);
+    assert_eq!(instruction.program_id, program_authority_escrow::id());
+    assert_eq!(
+        instruction.accounts,
+        vec![
+            AccountMeta::new_readonly(program, false),
+            AccountMeta::new_readonly(program_data_address(&program), false),
+            AccountMeta::new_readonly(bpf_loader_upgradeable::id(), false),
+        ]
+    );
+    assert_eq!(
+        instruction.data,
+        [
+            sighash("status"),
+            vec![1, 0, 0, 0],
+            candidate.to_bytes().to_vec(),
+            vec![1, 0, 0, 0],
+            1700000000i64.to_le_bytes().to_vec(),
+            vec![1, 0, 0, 0],
+            7u64.to_le_bytes().to_vec(),
+        ]
+        .concat()
+    );
+}
+
+#[test]
+fn test_scoped_and_immutable_ix() {
+    let current_authority = Pubkey::new_unique();
+    let new_authority = Pubkey::new_unique();
+    let program = Pubkey::new_unique();
+    let scoped_authority = scoped_escrow_authority(&current_authority, &new_authority, &program, 7);
+
+    let instruction = propose_scoped_ix(&current_authority, &new_authority, &program, 7, None);
+    assert_eq!(instruction.program_id, program_authority_escrow::id());
+    assert_eq!(
+        instruction.accounts,
+        vec![
+            AccountMeta::new_readonly(current_authority, true),
+            AccountMeta::new_readonly(new_authority, false),
+            AccountMeta::new_readonly(scoped_authority, false),
+            AccountMeta::new_readonly(program, false),
+            AccountMeta::new(program_data_address(&program), false),
+            AccountMeta::new_readonly(bpf_loader_upgradeable::id(), false),
+        ]
+    );
+    assert_eq!(
+        instruction.data,
+        [
+            sighash("propose_scoped"),
+            7u64.to_le_bytes().to_vec(),
+            vec![0]
+        ]
+        .concat()
+    );
+
+    let instruction = accept_scoped_ix(
+        &current_authority,
+        &new_authority,
+        &program,
+        7,
+        Some(HandoverMemo::Hash([3; 32])),
+    );
+    assert_eq!(
+        instruction.accounts[1],
+        AccountMeta::new_readonly(new_authority, true)
+    );
+    assert_eq!(instruction.accounts[2].pubkey, scoped_authority);
+    assert_eq!(
+        instruction.data,
+        [
+            sighash("accept_scoped"),
+            7u64.to_le_bytes().to_vec(),
+            vec![1, 1],
+            vec![3; 32]
+        ]
+        .concat()
+    );
+
+    let instruction = propose_immutable_ix(&current_authority, &new_authority, &program);
+    assert_eq!(
+        instruction.accounts[2].pubkey,
+        immutable_authority(&current_authority, &new_authority)
+    );
+    assert_eq!(instruction.data, sighash("propose_immutable"));
+
+    let instruction = confirm_immutable_ix(&current_authority, &new_authority, &program);
+    assert_eq!(
+        instruction.accounts[..2],
+        [
+            AccountMeta::new_readonly(current_authority, false),
+            AccountMeta::new_readonly(new_authority, true),
+        ]
+    );
+    assert_eq!(instruction.data, sighash("confirm_immutable"));
+}
+
+#[test]
+fn test_record_ix() {
+    let current_authority = Pubkey::new_unique();
+    let new_authority = Pubkey::new_unique();
+    let payer = Pubkey::new_unique();
+    let program = Pubkey::new_unique();
+    let timestamp: i64 = 1700000000;
+    let proposal_record = proposal_record_address(&current_authority, &new_authority, &program);
+
+    let instruction =
+        propose_with_record_ix(&current_authority, &new_authority, &payer, &program, None);
+    assert_eq!(
+        instruction.accounts,
+        vec![
+            AccountMeta::new_readonly(current_authority, true),
+            AccountMeta::new_readonly(new_authority, false),
+            AccountMeta::new_readonly(escrow_authority(&current_authority, &new_authority), false),
+            AccountMeta::new(proposal_record, false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(program, false),
+            AccountMeta::new(program_data_address(&program), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(bpf_loader_upgradeable::id(), false),
+        ]
+    );
+    assert_eq!(
+        instruction.data,
+        [sighash("propose_with_record"), vec![0]].concat()
+    );
+
+    // Anyone can close the record, nobody signs
+    let instruction =
+        close_proposal_record_ix(&current_authority, &new_authority, &payer, &program);
+    assert_eq!(
+        instruction.accounts,
+        vec![
+            AccountMeta::new(proposal_record, false),
+            AccountMeta::new(payer, false),
+            AccountMeta::new_readonly(escrow_authority(&current_authority, &new_authority), false),
+            AccountMeta::new_readonly(program, false),
+            AccountMeta::new_readonly(program_data_address(&program), false),
+            AccountMeta::new_readonly(bpf_loader_upgradeable::id(), false),
+        ]
+    );
+    assert_eq!(instruction.data, sighash("close_proposal_record"));
+
+    let instruction = commit_with_record_ix(
+        &current_authority,
+        &new_authority,
+        &payer,
+        &program,
+        timestamp,
+        None,
+    );
+    assert_eq!(instruction.program_id, program_authority_timelock::id());
+    assert_eq!(
+        instruction.accounts[3],
+        AccountMeta::new(
+            commitment_record_address(&new_authority, timestamp, &program),
+            false
+        )
+    );
+
+    let instruction = close_commitment_record_ix(&new_authority, &payer, &program, timestamp);
+    assert_eq!(
+        instruction.accounts[..3],
+        [
+            AccountMeta::new(
+                commitment_record_address(&new_authority, timestamp, &program),
+                false
+            ),
+            AccountMeta::new(payer, false),
+            AccountMeta::new_readonly(timelock_authority(&new_authority, timestamp), false),
+        ]
+    );
+    assert_eq!(instruction.data, sighash("close_commitment_record"));
+}
+
+#[test]
+fn test_strict_and_checked_ix() {
+    let current_authority = Pubkey::new_unique();
+    let new_authority = Pubkey::new_unique();
+    let program = Pubkey::new_unique();
+    let timestamp: i64 = 1700000000;
+
+    // Same accounts as the plain instructions, only the data differs
+    let instruction = propose_strict_ix(&current_authority, &new_authority, &program, None);
+    assert_eq!(
+        instruction.accounts,
+        propose_ix(&current_authority, &new_authority, &program, None).accounts
+    );
+    assert_eq!(
+        instruction.data,
+        [sighash("propose_strict"), vec![0]].concat()
+    );
+
+    let instruction = accept_checked_ix(&current_authority, &new_authority, &program, None);
+    assert_eq!(
+        instruction.accounts,
+        accept_ix(&current_authority, &new_authority, &program, None).accounts
+    );
+    assert_eq!(
+        instruction.data,
+        [sighash("accept_checked"), vec![0]].concat()
+    );
+
+    let instruction = commit_strict_ix(
+        &current_authority,
+        &new_authority,
+        &program,
+        timestamp,
+        None,
+    );
+    assert_eq!(
+        instruction.accounts,
+        commit_ix(
+            &current_authority,
+            &new_authority,
+            &program,
+            timestamp,
+            None
+        )
+        .accounts
+    );
+    assert_eq!(
+        instruction.data,
+        [
+            sighash("commit_strict"),
+            timestamp.to_le_bytes().to_vec(),
+            vec![0]
+        ]
+        .concat()
+    );
+
+    // Unlike `transfer`, the new authority signs
+    let instruction = transfer_checked_ix(&new_authority, &program, timestamp, None);
+    assert_eq!(
+        instruction.accounts[0],
+        AccountMeta::new_readonly(new_authority, true)
+    );
+    assert_eq!(
+        instruction.data,
+        [
+            sighash("transfer_checked"),
+            timestamp.to_le_bytes().to_vec(),
+            vec![0]
+        ]
+        .concat()
+    );
+}
+
+#[test]
+fn test_pda() {
+    let current_authority = Pubkey::new_unique();
+    let new_authority = Pubkey::new_unique();
+    let program = Pubkey::new_unique();
+
+    assert_eq!(
+        program_data_address(&program),
+        Pubkey::find_program_address(&[program.as_ref()], &bpf_loader_upgradeable::id()).0
+    );
+    assert_eq!(
+        escrow_authority(&current_authority, &new_authority),
+        Pubkey::find_program_address(
+            &[current_authority.as_ref(), new_authority.as_ref()],
+            &program_authority_escrow::id()
+        )
+        .0
+    );
+    // Each handover between the same two authorities gets its own escrow authority
+    assert_ne!(
+        escrow_authority(&current_authority, &new_authority),
+        escrow_authority(&new_authority, &current_authority)
+    );
+    assert_ne!(
+        timelock_authority(&new_authority, 0),
+        timelock_authority(&new_authority, 1)
+    );
+    assert_ne!(
+        proposal_record_address(&current_authority, &new_authority, &program),
+        proposal_record_address(&current_authority, &new_authority, &Pubkey::new_unique())
+    );
+}
+
+#[test]
+fn test_decode() {
+    let program_data = Pubkey::new_unique();
+    let upgrade_authority = Pubkey::new_unique();
+
+    let data = bincode::serialize(&UpgradeableLoaderState::Program {
+        programdata_address: program_data,
+    })
+    .unwrap();
+    assert_eq!(decode_program(&data).unwrap(), program_data);
+    assert!(decode_program_data(&data).is_err());
+
+    let mut data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+        slot:                      42,
+        upgrade_authority_address: Some(upgrade_authority),
+    })
+    .unwrap();
+    // The program binary follows the header
+    data.extend_from_slice(&[1, 2, 3]);
+    let decoded = decode_program_data(&data).unwrap();
+    assert_eq!(decoded.slot, 42);
+    assert_eq!(decoded.upgrade_authority_address, Some(upgrade_authority));
+    assert!(decode_program(&data).is_err());
+
+    let data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+        slot:                      42,
+        upgrade_authority_address: None,
+    })
+    .unwrap();
+    assert_eq!(decode_upgrade_authority(&data).unwrap(), None);
+}
+
+#[test]
+fn test_offline_round_trip() {
+    let current_authority = Pubkey::new_unique();
+    let new_authority = Pubkey::new_unique();
+    let program = Pubkey::new_unique();
+
+    let instruction = propose_ix(
+        &current_authority,
+        &new_authority,
+        &program,
+        Some(HandoverMemo::Reason("Rotate keys".to_string())),
+    );
+    for encoding in [Encoding::Base58, Encoding::Base64] {
+        let encoded = encode(&instruction, encoding);
+        assert_eq!(
+            decode::<Instruction>(&encoded, encoding).unwrap(),
+            instruction
+        );
+    }
+
+    let nonce_account = Pubkey::new_unique();
+    let instructions = with_durable_nonce(&nonce_account, &current_authority, &[instruction]);
+    assert_eq!(
+        instructions[0],
+        system_instruction::advance_nonce_account(&nonce_account, &current_authority)
+    );
+
+    let message =
+        Message::new_with_blockhash(&instructions, Some(&current_authority), &Hash::new_unique());
+    for encoding in [Encoding::Base58, Encoding::Base64] {
+        let encoded = encode(&message, encoding);
+        assert_eq!(decode::<Message>(&encoded, encoding).unwrap(), message);
+    }
+
+    assert!(decode::<Message>("not base64!", Encoding::Base64).is_err());
+}
+
+#[test]
+fn test_inspect_authority() {
+    let program = Pubkey::new_unique();
+    let authority_1 = Pubkey::new_unique();
+    let authority_2 = Pubkey::new_unique();
+    let candidates = [Pubkey::new_unique(), authority_1, authority_2];
+    let timestamps = [0, 1700000000];
+    let nonces = [0, 7];
+    let inspect = |upgrade_authority: Option<Pubkey>, now: i64| {
+        inspect_authority(
+            &program,
+            upgrade_authority,
+            &candidates,
+            &timestamps,
+            &nonces,
+            now,
+        )
+    };
+
+    assert_eq!(inspect(None, 0), AuthorityStatus::Immutable);
+    assert_eq!(
+        inspect(Some(authority_1), 0),
+        AuthorityStatus::Direct(authority_1)
+    );
+    assert_eq!(
+        inspect(Some(escrow_authority(&authority_2, &authority_1)), 0),
+        AuthorityStatus::Escrowed {
+            current_authority: authority_2,
+            new_authority:     authority_1,
+        }
+    );
+    assert_eq!(
+        inspect(
+            Some(scoped_escrow_authority(
+                &authority_1,
+                &authority_2,
+                &program,
+                7
+            )),
+            0
+        ),
+        AuthorityStatus::ScopedEscrowed {
+            current_authority: authority_1,
+            new_authority:     authority_2,
+            nonce:             7,
+        }
+    );
+    assert_eq!(
+        inspect(Some(immutable_authority(&authority_1, &authority_2)), 0),
+        AuthorityStatus::PendingImmutable {
+            current_authority: authority_1,
+            confirm_authority: authority_2,
+        }
+    );
+
+    let timelock = timelock_authority(&authority_2, 1700000000);
+    assert_eq!(
+        inspect(Some(timelock), 1700000000 - 1),
+        AuthorityStatus::Timelocked {
+            new_authority: authority_2,
+            timestamp:     1700000000,
+            matured:       false,
+        }
+    );
+    assert_eq!(
+        inspect(Some(timelock), 1700000000),
+        AuthorityStatus::Timelocked {
+            new_authority: authority_2,
+            timestamp:     1700000000,
+            matured:       true,
+        }
+    );
+
+    // Without the right candidates a PDA looks like any other key
+    assert_eq!(
+        inspect_authority(
+            &program,
+            Some(timelock),
+            &[authority_1],
+            &timestamps,
+            &[],
+            0
+        ),
+        AuthorityStatus::Direct(timelock)
+    );
+}
+
+#[test]
+fn test_derivation_path() {
+    assert_eq!(
+        derivation_path(None, None),
+        vec![2, 0x80, 0, 0, 44, 0x80, 0, 0x01, 0xf5]
+    );
+    assert_eq!(
+        derivation_path(Some(1), None),
+        vec![3, 0x80, 0, 0, 44, 0x80, 0, 0x01, 0xf5, 0x80, 0, 0, 1]
+    );
+    assert_eq!(
+        derivation_path(Some(1), Some(2)),
+        vec![4, 0x80, 0, 0, 44, 0x80, 0, 0x01, 0xf5, 0x80, 0, 0, 1, 0x80, 0, 0, 2]
+    );
+    // Like the TypeScript wallet, the change needs an account
+    assert_eq!(derivation_path(None, Some(2)), derivation_path(None, None));
+}
+
+#[test]
+fn test_ledger_signer() {
+    let keypair = Keypair::new();
+    let pubkey = keypair.pubkey();
+    let signer = LedgerSigner::new(MockTransport::new(keypair), Some(0), Some(0)).unwrap();
+    assert_eq!(signer.pubkey(), pubkey);
+
+    let path = derivation_path(Some(0), Some(0));
+    let mut get_pubkey = vec![0xe0, 0x05, 0x00, 0x00, path.len() as u8];
+    get_pubkey.extend(&path);
+    assert_eq!(signer.get_transport().get_apdus(), vec![get_pubkey]);
+
+    // 1 + 17 + 600 bytes are sent in chunks of 255, 255 and 108 bytes
+    let message = vec![7; 600];
+    let signature = signer.try_sign_message(&message).unwrap();
+    assert!(signature.verify(pubkey.as_ref(), &message));
+
+    let apdus = signer.get_transport().get_apdus();
+    assert_eq!(apdus.len(), 4);
+    let headers: Vec<&[u8]> = apdus[1..].iter().map(|apdu| &apdu[..5]).collect();
+    assert_eq!(
+        headers,
+        vec![
+            [0xe0, 0x06, 0x01, 0x02, 255].as_ref(),
+            [0xe0, 0x06, 0x01, 0x03, 255].as_ref(),
+            [0xe0, 0x06, 0x01, 0x01, 108].as_ref(),
+        ]
+    );
+    let payload: Vec<u8> = apdus[1..]
+        .iter()
+        .flat_map(|apdu| apdu[5..].to_vec())
+        .collect();
+    assert_eq!(payload[0], 1);
+    assert_eq!(payload[1..1 + path.len()], path);
+    assert_eq!(payload[1 + path.len()..], message);
+
+    // A short message fits in a single APDU
+    let signature = signer.try_sign_message(&[1, 2, 3]).unwrap();
+    assert!(signature.verify(pubkey.as_ref(), &[1, 2, 3]));
+    assert_eq!(
+        signer.get_transport().get_apdus()[4][..5],
+        [0xe0, 0x06, 0x01, 0x00, 21]
+    );
+
+    let signer = LedgerSigner::new(MockTransport::refusing(Keypair::new()), None, None).unwrap();
+    assert!(matches!(
+        signer.try_sign_message(&message),
+        Err(SignerError::UserCancel(_))
+    ));
+}
+
+#[test]
+fn test_decode_handover() {
+    let current_authority = Pubkey::new_unique();
+    let new_authority = Pubkey::new_unique();
+    let program = Pubkey::new_unique();
+    let decoded = |instruction: Instruction| {
+        let accounts: Vec<Pubkey> = instruction
+            .accounts
+            .iter()
+            .map(|meta| meta.pubkey)
+            .collect();
+        decode_handover(&instruction.program_id, &accounts, &instruction.data).unwrap()
+    };
+
+    assert_eq!(
+        decoded(propose_ix(
+            &current_authority,
+            &new_authority,
+            &program,
+            Some(HandoverMemo::Hash([1; 32]))
+        )),
+        DecodedHandover {
+            instruction:       "propose_with_memo",
+            program_id:        program_authority_escrow::id(),
+            program_account:   program,
+            current_authority: Some(current_authority),
+            new_authority:     new_authority,
+            next_authority:    None,
+            timestamp:         None,
+            nonce:             None,
+            memo:              Some(format!("hash:{}", Hash::new_from_array([1; 32]))),
+        }
+    );
+    assert_eq!(
+        decoded(transfer_ix(&new_authority, &program, 1700000000, None)),
+        DecodedHandover {
+            instruction:       "transfer",
+            program_id:        program_authority_timelock::id(),
+            program_account:   program,
+            current_authority: None,
+            new_authority:     new_authority,
+            next_authority:    None,
+            timestamp:         Some(1700000000),
+            nonce:             None,
+            memo:              None,
+        }
+    );
+
+    // Chained, scoped and immutable handovers of the escrow
+    let next_authority = Pubkey::new_unique();
+    let escrow_instruction = |accounts: Vec<AccountMeta>, data: Vec<u8>| Instruction {
+        program_id: program_authority_escrow::id(),
+        accounts,
+        data,
+    };
+    let handover = decoded(escrow_instruction(
+        program_authority_escrow::accounts::AcceptAndPropose {
+            current_authority,
+            new_authority,
+            escrow_authority: escrow_authority(&current_authority, &new_authority),
+            next_authority,
+            next_escrow_authority: escrow_authority(&new_authority, &next_authority),
+            program_account: program,
+            program_data: program_data_address(&program),
+            bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+        }
+        .to_account_metas(None),
+        program_authority_escrow::instruction::AcceptAndPropose { memo: None }.data(),
+    ));
+    assert_eq!(handover.instruction, "accept_and_propose");
+    assert_eq!(handover.current_authority, Some(current_authority));
+    assert_eq!(handover.new_authority, new_authority);
+    assert_eq!(handover.next_authority, Some(next_authority));
+
+    let handover = decoded(escrow_instruction(
+        program_authority_escrow::accounts::AcceptScoped {
+            current_authority,
+            new_authority,
+            escrow_authority: scoped_escrow_authority(
+                &current_authority,
+                &new_authority,
+                &program,
+                7,
+            ),
+            program_account: program,
+            program_data: program_data_address(&program),
+            bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+        }
+        .to_account_metas(None),
+        program_authority_escrow::instruction::AcceptScoped {
+            nonce: 7,
+            memo:  Some(HandoverMemo::Reason("Scoped".to_string())),
+        }
+        .data(),
+    ));
+    assert_eq!(handover.instruction, "accept_scoped");
+    assert_eq!(handover.nonce, Some(7));
+    assert_eq!(handover.memo, Some("Scoped".to_string()));
+
+    let handover = decoded(escrow_instruction(
+        program_authority_escrow::accounts::ConfirmImmutable {
+            current_authority,
+            confirm_authority: new_authority,
+            immutable_authority: immutable_authority(&current_authority, &new_authority),
+            program_account: program,
+            program_data: program_data_address(&program),
+            bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+        }
+        .to_account_metas(None),
+        program_authority_escrow::instruction::ConfirmImmutable.data(),
+    ));
+    assert_eq!(handover.instruction, "confirm_immutable");
+    assert_eq!(handover.new_authority, new_authority);
+
+    // Other instructions of the programs and other programs are left out
+    let status = Instruction {
+        data: sighash("status"),
+        ..propose_ix(&current_authority, &new_authority, &program, None)
+    };
+    assert_eq!(decode_handover(&status.program_id, &[], &status.data), None);
+    assert_eq!(
+        decode_handover(&Pubkey::new_unique(), &[], &sighash("propose")),
+        None
+    );
+}
+
+/// The fixtures follow the `getTransaction` format with the `json` encoding
+#[test]
+fn test_decode_transaction() {
+    let transactions: Vec<serde_json::Value> = serde_json::from_str(
+        &std::fs::read_to_string("../tests/fixtures/history/transactions.json").unwrap(),
+    )
+    .unwrap();
+    let events: Vec<_> = transactions
+        .into_iter()
+        .flat_map(|transaction| decode_transaction(transaction).unwrap())
+        .collect();
+
+    let program_1 = Pubkey::from_str("C6iRNYLu7yqVbF7aczpmVuCAXz7JTRUr3VEdtYg69wDa").unwrap();
+    let program_2 = Pubkey::from_str("56maDTS4GcRmCGh6PWyKAQnA1Y1WSnsEFSJM8Cbjzkrb").unwrap();
+    let mut slots: Vec<_> = events.iter().map(|event| event.slot).collect();
+    slots.sort();
+    // The compute budget instruction and the top-level call to the CPI example are left out
+    assert_eq!(
+        slots,
+        vec![1000, 1005, 1010, 1020, 1020, 1030, 1040, 1050, 1060, 1100]
+    );
+
+    let event = |slot| events.iter().find(|event| event.slot == slot).unwrap();
+    assert_eq!(event(1000).handover.instruction, "propose_with_memo");
+    assert_eq!(event(1000).handover.program_account, program_1);
+    assert_eq!(
+        event(1000).handover.memo.as_deref(),
+        Some("Rotate to the new multisig, approved in \"Q4 review\"")
+    );
+    assert!(!event(1030).success);
+    assert_eq!(event(1040).handover.instruction, "propose_strict");
+    assert!(event(1040)
+        .handover
+        .memo
+        .as_ref()
+        .unwrap()
+        .starts_with("hash:"));
+    assert_eq!(event(1100).handover.timestamp, Some(1700000000));
+
+    // Accepted by a PDA through CPI, in a transaction loading accounts from a lookup table
+    assert_eq!(event(1060).handover.instruction, "accept");
+    assert_eq!(event(1060).handover.program_account, program_2);
+    assert_eq!(
+        event(1060).handover.new_authority,
+        Pubkey::from_str("G1SHgro6J6UCTXdPqcxDwgUAWg8VfrXQ8vgvwvG7MUST").unwrap()
+    );
+    assert_eq!(
+        event(1060).handover.current_authority,
+        event(1005).handover.current_authority
+    );
+}
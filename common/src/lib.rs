@@ -0,0 +1,213 @@
+#![deny(warnings)]
+
+//! Ids, seeds, types and checks shared by the escrow and the timelock. Both programs re-export the
+//! types from here, so their instructions, events and `status` agree on a single definition, and
+//! the timelock finds the escrow's addresses without depending on the escrow crate.
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        hash::Hash,
+        system_program,
+    },
+};
+
+/// Same id as the `declare_id!` of the escrow program
+pub mod escrow_program {
+    anchor_lang::declare_id!("escMHe7kSqPcDHx4HU44rAHhgdTLBZkUrU39aN8kMcL");
+}
+
+/// Same id as the `declare_id!` of the timelock program
+pub mod timelock_program {
+    anchor_lang::declare_id!("t1monUESMN3oVEoAw9HQkaVX6hUGg3hkhN5wKaTvV5f");
+}
+
+/// Programs whose accounts strict mode accepts as a new authority when it is off-curve. SPL
+/// Governance holds upgrade authorities in governance accounts it owns. Vaults of multisigs like
+/// Squads are PDAs owned by the system program, they go through the non-strict instructions
+pub mod strict_owner_allowlist {
+    pub mod spl_governance {
+        anchor_lang::declare_id!("GovER5Lthms3bLBqWub97yVrMmEogzX7xNjdXpPPCVZw");
+    }
+}
+
+/// Seed of the escrow authorities of `propose_immutable`
+pub const IMMUTABLE_SEED: &[u8] = b"immutable";
+pub const MAX_REASON_LEN: usize = 200;
+/// Caps on the arguments of `status`, sized for an address book. Each address it derives costs
+/// 1500 compute units per bump tried, 2 bumps on average. When nothing matches, the caps make it
+/// derive 8 * 8 * (2 + 2) escrow and 8 * 4 timelock addresses, 288 in all or about 870k units,
+/// which `test_status_compute_budget` measures against `STATUS_COMPUTE_UNIT_LIMIT`
+pub const MAX_STATUS_CANDIDATES: usize = 8;
+pub const MAX_STATUS_TIMESTAMPS: usize = 4;
+pub const MAX_STATUS_NONCES: usize = 2;
+/// Compute unit limit to request for `status` past a few candidates, the default of 200k units
+/// per instruction only covers about 65 addresses. It's the maximum of a transaction
+pub const STATUS_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Justification attached to an authority handover, written out or as the hash of an external
+/// document
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum HandoverMemo {
+    Reason(String),
+    Hash([u8; 32]),
+}
+
+/// Why a memo can't be attached to a handover
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvalidMemo {
+    EmptyReason,
+    ReasonTooLong,
+}
+
+/// Rejects empty or oversized reasons and writes the memo to the program logs
+pub fn log_memo(memo: &Option<HandoverMemo>) -> std::result::Result<(), InvalidMemo> {
+    match memo {
+        Some(HandoverMemo::Reason(reason)) => {
+            if reason.is_empty() {
+                return Err(InvalidMemo::EmptyReason);
+            }
+            if reason.len() > MAX_REASON_LEN {
+                return Err(InvalidMemo::ReasonTooLong);
+            }
+            msg!("Reason: {}", reason);
+        }
+        Some(HandoverMemo::Hash(hash)) => {
+            msg!("Reference hash: {}", Hash::new_from_array(*hash));
+        }
+        None => {}
+    }
+    Ok(())
+}
+
+/// Who controls a program, as returned by `status`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum AuthorityStatus {
+    Direct(Pubkey),
+    Escrowed {
+        current_authority: Pubkey,
+        new_authority:     Pubkey,
+    },
+    ScopedEscrowed {
+        current_authority: Pubkey,
+        new_authority:     Pubkey,
+        nonce:             u64,
+    },
+    PendingImmutable {
+        current_authority: Pubkey,
+        confirm_authority: Pubkey,
+    },
+    Timelocked {
+        new_authority: Pubkey,
+        timestamp:     i64,
+        matured:       bool,
+    },
+    Immutable,
+}
+
+/// Matches the upgrade authority of `program` against the escrow addresses of every pair of
+/// candidates, scoped to every nonce or pending immutability, and the timelock addresses of every
+/// candidate and timestamp
+pub fn find_authority_status(
+    program: &Pubkey,
+    upgrade_authority: Option<Pubkey>,
+    candidates: &[Pubkey],
+    timestamps: &[i64],
+    nonces: &[u64],
+    now: i64,
+) -> AuthorityStatus {
+    let upgrade_authority = match upgrade_authority {
+        Some(upgrade_authority) => upgrade_authority,
+        None => return AuthorityStatus::Immutable,
+    };
+    let is_escrow_authority = |seeds: &[&[u8]]| {
+        Pubkey::find_program_address(seeds, &escrow_program::ID).0 == upgrade_authority
+    };
+
+    for current_authority in candidates.iter() {
+        for new_authority in candidates.iter() {
+            if is_escrow_authority(&[current_authority.as_ref(), new_authority.as_ref()]) {
+                return AuthorityStatus::Escrowed {
+                    current_authority: *current_authority,
+                    new_authority:     *new_authority,
+                };
+            }
+            for nonce in nonces.iter() {
+                if is_escrow_authority(&[
+                    current_authority.as_ref(),
+                    new_authority.as_ref(),
+                    program.as_ref(),
+                    nonce.to_be_bytes().as_ref(),
+                ]) {
+                    return AuthorityStatus::ScopedEscrowed {
+                        current_authority: *current_authority,
+                        new_authority:     *new_authority,
+                        nonce:             *nonce,
+                    };
+                }
+            }
+            if is_escrow_authority(&[
+                IMMUTABLE_SEED,
+                current_authority.as_ref(),
+                new_authority.as_ref(),
+            ]) {
+                return AuthorityStatus::PendingImmutable {
+                    current_authority: *current_authority,
+                    confirm_authority: *new_authority,
+                };
+            }
+        }
+    }
+
+    for new_authority in candidates.iter() {
+        for timestamp in timestamps.iter() {
+            let timelock_authority = Pubkey::find_program_address(
+                &[new_authority.as_ref(), timestamp.to_be_bytes().as_ref()],
+                &timelock_program::ID,
+            )
+            .0;
+            if timelock_authority == upgrade_authority {
+                return AuthorityStatus::Timelocked {
+                    new_authority: *new_authority,
+                    timestamp:     *timestamp,
+                    matured:       now >= *timestamp,
+                };
+            }
+        }
+    }
+
+    AuthorityStatus::Direct(upgrade_authority)
+}
+
+/// Why a new authority could never sign for a program
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnusableAuthority {
+    SystemProgram,
+    HandoverProgram,
+    SameAuthority,
+}
+
+/// Finds new authorities that can never sign for the program, `handover_program` being the
+/// escrow or the timelock that would hold it in between
+pub fn find_unusable_authority(
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+    handover_program: &Pubkey,
+) -> Option<UnusableAuthority> {
+    // The system program id is also `Pubkey::default()`
+    if *new_authority == system_program::ID {
+        Some(UnusableAuthority::SystemProgram)
+    } else if new_authority == handover_program {
+        Some(UnusableAuthority::HandoverProgram)
+    } else if new_authority == current_authority {
+        Some(UnusableAuthority::SameAuthority)
+    } else {
+        None
+    }
+}
+
+/// Strict mode only accepts wallets or accounts owned by an allowlisted program
+pub fn is_strict_authority(new_authority: &AccountInfo) -> bool {
+    new_authority.key().is_on_curve()
+        || strict_owner_allowlist::spl_governance::check_id(new_authority.owner)
+}
@@ -0,0 +1,107 @@
+use {
+    crate::{
+        EntryState,
+        WatchEntry,
+        Watchlist,
+    },
+    anyhow::{
+        anyhow,
+        Result,
+    },
+    program_authority_client::Cluster,
+    solana_sdk::{
+        signature::{
+            Keypair,
+            Signature,
+            Signer,
+        },
+        transaction::Transaction,
+    },
+    std::{
+        fmt,
+        path::Path,
+    },
+};
+
+/// What a pass did with a due handover
+#[derive(Debug)]
+pub enum CrankEvent {
+    Transferred {
+        entry:     WatchEntry,
+        signature: Signature,
+    },
+    AlreadyTransferred {
+        entry: WatchEntry,
+    },
+    Failed {
+        entry: WatchEntry,
+        error: anyhow::Error,
+    },
+}
+
+impl fmt::Display for CrankEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CrankEvent::Transferred { entry, signature } => write!(
+                f,
+                "Transferred {} to {}: {signature}",
+                entry.program, entry.new_authority
+            ),
+            CrankEvent::AlreadyTransferred { entry } => write!(
+                f,
+                "{} was already transferred to {}",
+                entry.program, entry.new_authority
+            ),
+            CrankEvent::Failed { entry, error } => {
+                write!(f, "Failed to transfer {}: {error}", entry.program)
+            }
+        }
+    }
+}
+
+/// One pass over the watch list. It is read again every time so that `watch` works while the crank
+/// runs. Transferred handovers leave the list, failed ones are retried after a backoff
+pub fn crank(cluster: &impl Cluster, state: &Path, fee_payer: &Keypair) -> Result<Vec<CrankEvent>> {
+    let now = cluster.get_clock()?.unix_timestamp;
+
+    let mut events = vec![];
+    for entry in Watchlist::load(state)?.due(now) {
+        let result =
+            cluster
+                .get_upgrade_authority(&entry.program)
+                .and_then(|authority| match entry.state(authority) {
+                    EntryState::Locked => transfer(cluster, &entry, fee_payer).map(Some),
+                    EntryState::Transferred => Ok(None),
+                    EntryState::Waiting => Err(anyhow!("Not held by the timelock yet")),
+                });
+        events.push(match result {
+            Ok(Some(signature)) => CrankEvent::Transferred { entry, signature },
+            Ok(None) => CrankEvent::AlreadyTransferred { entry },
+            Err(error) => CrankEvent::Failed { entry, error },
+        });
+    }
+
+    // `watch` may have written the file while the transfers were sent, the outcomes are applied to
+    // a fresh copy right before saving so its entries aren't lost
+    let mut watchlist = Watchlist::load(state)?;
+    for event in events.iter() {
+        match event {
+            CrankEvent::Transferred { entry, .. } | CrankEvent::AlreadyTransferred { entry } => {
+                watchlist.remove(entry)
+            }
+            CrankEvent::Failed { entry, .. } => watchlist.record_failure(entry, now),
+        }
+    }
+    watchlist.save()?;
+    Ok(events)
+}
+
+fn transfer(cluster: &impl Cluster, entry: &WatchEntry, fee_payer: &Keypair) -> Result<Signature> {
+    let transaction = Transaction::new_signed_with_payer(
+        &[entry.transfer_ix()],
+        Some(&fee_payer.pubkey()),
+        &[fee_payer],
+        cluster.get_latest_blockhash()?,
+    );
+    cluster.send_transaction(&transaction)
+}
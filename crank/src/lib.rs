@@ -0,0 +1,15 @@
+#![deny(warnings)]
+
+//! Keeps a list of timelocked programs and transfers each of them to its new authority as soon as
+//! the cluster clock passes its timestamp.
+
+mod crank;
+mod watchlist;
+
+pub use {
+    crank::*,
+    watchlist::*,
+};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,116 @@
+use {
+    anyhow::Result,
+    clap::{
+        Parser,
+        Subcommand,
+    },
+    program_authority_client::read_keypair,
+    program_authority_crank::{
+        crank,
+        CrankEvent,
+        WatchEntry,
+        Watchlist,
+    },
+    solana_client::rpc_client::RpcClient,
+    solana_sdk::{
+        commitment_config::CommitmentConfig,
+        pubkey::Pubkey,
+    },
+    std::{
+        path::PathBuf,
+        thread::sleep,
+        time::Duration,
+    },
+};
+
+#[derive(Parser, Debug)]
+#[clap(
+    name = "program-authority-crank",
+    about = "Transfers upgrade authorities out of the timelock as soon as they mature"
+)]
+struct Cli {
+    /// RPC URL of the cluster
+    #[clap(
+        long,
+        short = 'u',
+        global = true,
+        default_value = "http://localhost:8899"
+    )]
+    url:     String,
+    /// File keeping the watch list across restarts
+    #[clap(long, global = true, default_value = "crank.json")]
+    state:   PathBuf,
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Adds a timelocked program to the watch list, the commit can come later
+    Watch {
+        #[clap(long, value_parser)]
+        program:       Pubkey,
+        #[clap(long, value_parser)]
+        new_authority: Pubkey,
+        #[clap(long, value_parser)]
+        timestamp:     i64,
+    },
+    /// Prints the watch list
+    List,
+    /// Sends the transfers of the watched programs as they mature, until interrupted
+    Run {
+        /// Keypair file of the account paying the transaction fees
+        #[clap(long, default_value = "~/.config/solana/id.json")]
+        fee_payer: PathBuf,
+        /// Seconds between two passes over the watch list
+        #[clap(long, default_value = "10")]
+        interval:  u64,
+    },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Watch {
+            program,
+            new_authority,
+            timestamp,
+        } => {
+            let mut watchlist = Watchlist::load(&cli.state)?;
+            watchlist.watch(WatchEntry::new(program, new_authority, timestamp));
+            watchlist.save()?;
+        }
+        Command::List => {
+            for entry in Watchlist::load(&cli.state)?.get_entries() {
+                println!(
+                    "{} to {} at {}, {} failed attempts",
+                    entry.program, entry.new_authority, entry.timestamp, entry.attempts
+                );
+            }
+        }
+        Command::Run {
+            fee_payer,
+            interval,
+        } => {
+            let rpc_client =
+                RpcClient::new_with_commitment(cli.url.clone(), CommitmentConfig::confirmed());
+            let fee_payer = read_keypair(&fee_payer)?;
+            loop {
+                match crank(&rpc_client, &cli.state, &fee_payer) {
+                    Ok(events) => {
+                        for event in events.iter() {
+                            match event {
+                                CrankEvent::Failed { .. } => eprintln!("{event}"),
+                                _ => println!("{event}"),
+                            }
+                        }
+                    }
+                    Err(err) => eprintln!("{err}"),
+                }
+                sleep(Duration::from_secs(interval));
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,286 @@
+use {
+    crate::{
+        crank,
+        CrankEvent,
+        WatchEntry,
+        Watchlist,
+    },
+    anchor_lang::prelude::{
+        Clock,
+        Pubkey,
+        Rent,
+        UpgradeableLoaderState,
+    },
+    program_authority_client::Cluster,
+    solana_program_test::{
+        read_file,
+        BanksClientError,
+        ProgramTest,
+        ProgramTestContext,
+    },
+    solana_sdk::{
+        account::Account,
+        bpf_loader_upgradeable,
+        hash::Hash,
+        signature::{
+            Keypair,
+            Signature,
+            Signer,
+        },
+        stake_history::Epoch,
+        transaction::Transaction,
+    },
+    std::{
+        future::Future,
+        path::PathBuf,
+    },
+    tokio::runtime::Handle,
+};
+
+
+pub struct CrankSimulator {
+    context:            ProgramTestContext,
+    helloworld_address: Pubkey,
+    state_path:         PathBuf,
+}
+
+impl CrankSimulator {
+    /// Deploys the timelock and an upgradable program, the watch list starts empty
+    pub async fn new() -> (CrankSimulator, Keypair) {
+        let mut bpf_data = read_file(PathBuf::from("../tests/fixtures/helloworld.so"));
+
+        let mut program_test = ProgramTest::new(
+            "program_authority_timelock",
+            program_authority_timelock::id(),
+            None,
+        );
+        let upgrade_authority = Keypair::new();
+
+        let helloworld_address = add_program_as_upgradable(
+            &mut bpf_data,
+            &upgrade_authority.pubkey(),
+            &mut program_test,
+        );
+
+        let context = program_test.start_with_context().await;
+
+        let state_path = std::env::temp_dir().join(format!(
+            "program-authority-crank-{}.json",
+            Pubkey::new_unique()
+        ));
+
+        (
+            CrankSimulator {
+                context,
+                helloworld_address,
+                state_path,
+            },
+            upgrade_authority,
+        )
+    }
+}
+
+pub fn add_program_as_upgradable(
+    data: &mut Vec<u8>,
+    upgrade_authority: &Pubkey,
+    program_test: &mut ProgramTest,
+) -> Pubkey {
+    let program_key = Pubkey::new_unique();
+    let (programdata_key, _) =
+        Pubkey::find_program_address(&[&program_key.to_bytes()], &bpf_loader_upgradeable::id());
+
+
+    let program_deserialized = UpgradeableLoaderState::Program {
+        programdata_address: programdata_key,
+    };
+    let programdata_deserialized = UpgradeableLoaderState::ProgramData {
+        slot:                      1,
+        upgrade_authority_address: Some(*upgrade_authority),
+    };
+
+    // Program contains a pointer to progradata
+    let program_vec = bincode::serialize(&program_deserialized).unwrap();
+    // Programdata contains a header and the binary of the program
+    let mut programdata_vec = bincode::serialize(&programdata_deserialized).unwrap();
+    programdata_vec.append(data);
+
+    let program_account = Account {
+        lamports:   Rent::default().minimum_balance(program_vec.len()),
+        data:       program_vec,
+        owner:      bpf_loader_upgradeable::ID,
+        executable: true,
+        rent_epoch: Epoch::default(),
+    };
+    let programdata_account = Account {
+        lamports:   Rent::default().minimum_balance(programdata_vec.len()),
+        data:       programdata_vec,
+        owner:      bpf_loader_upgradeable::ID,
+        executable: false,
+        rent_epoch: Epoch::default(),
+    };
+
+    // Add both accounts to program test, now the program is deployed as upgradable
+    program_test.add_account(program_key, program_account);
+    program_test.add_account(programdata_key, programdata_account);
+
+    program_key
+}
+
+
+impl CrankSimulator {
+    pub async fn commit(
+        &mut self,
+        current_authority: &Keypair,
+        new_authority: &Pubkey,
+        timestamp: i64,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::commit_ix(
+            &current_authority.pubkey(),
+            new_authority,
+            &self.helloworld_address,
+            timestamp,
+            None,
+        );
+        let blockhash = self.get_latest_blockhash().await;
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&self.context.payer.pubkey()),
+            &[&self.context.payer, current_authority],
+            blockhash,
+        );
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+    }
+
+    /// Same as the binary's `watch`
+    pub fn watch(&self, new_authority: &Pubkey, timestamp: i64) {
+        let mut watchlist = self.get_watchlist();
+        watchlist.watch(WatchEntry::new(
+            self.helloworld_address,
+            *new_authority,
+            timestamp,
+        ));
+        watchlist.save().unwrap();
+    }
+
+    /// One pass of the binary's `run`, `context.payer` pays the fees
+    pub fn crank(&self) -> Vec<CrankEvent> {
+        let fee_payer = Keypair::from_bytes(&self.context.payer.to_bytes()).unwrap();
+        self.crank_with_fee_payer(&fee_payer)
+    }
+
+    pub fn crank_with_fee_payer(&self, fee_payer: &Keypair) -> Vec<CrankEvent> {
+        crank(self, &self.state_path, fee_payer).unwrap()
+    }
+
+    /// Same as `crank`, with `entry` watched while the transfers are sent, like a `watch` command
+    /// running next to the crank
+    pub fn crank_while_watching(&self, entry: WatchEntry) -> Vec<CrankEvent> {
+        let fee_payer = Keypair::from_bytes(&self.context.payer.to_bytes()).unwrap();
+        let cluster = WatchingCluster {
+            simulator: self,
+            entry,
+        };
+        crank(&cluster, &self.state_path, &fee_payer).unwrap()
+    }
+
+    /// Reads the watch list back from its file, like a restarted crank
+    pub fn get_watchlist(&self) -> Watchlist {
+        Watchlist::load(&self.state_path).unwrap()
+    }
+
+    pub fn get_program(&self) -> Pubkey {
+        self.helloworld_address
+    }
+
+    async fn get_latest_blockhash(&mut self) -> Hash {
+        self.context
+            .banks_client
+            .get_latest_blockhash()
+            .await
+            .unwrap()
+    }
+
+    async fn get_clock(&mut self) -> Clock {
+        self.context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+    }
+
+    async fn get_upgrade_authority(&mut self, program: &Pubkey) -> Option<Pubkey> {
+        let account = self
+            .context
+            .banks_client
+            .get_account(program_authority_client::program_data_address(program))
+            .await
+            .unwrap()
+            .unwrap();
+        program_authority_client::decode_upgrade_authority(&account.data).unwrap()
+    }
+
+    pub async fn warp_to_timestamp(&mut self, timestamp: i64) {
+        let current_clock = self.get_clock().await;
+        self.context.set_sysvar::<Clock>(&Clock {
+            unix_timestamp: timestamp,
+            ..current_clock
+        });
+    }
+
+    pub async fn check_program_authority_matches(&mut self, upgrade_authority: &Pubkey) {
+        let program = self.helloworld_address;
+        assert_eq!(
+            self.get_upgrade_authority(&program).await,
+            Some(*upgrade_authority)
+        );
+    }
+}
+
+/// The crank reads the cluster synchronously, the calls block on the test runtime, which has to be
+/// the multi-threaded one
+impl Cluster for CrankSimulator {
+    fn get_account(&self, address: &Pubkey) -> anyhow::Result<Option<Account>> {
+        let mut banks_client = self.context.banks_client.clone();
+        Ok(block_on(banks_client.get_account(*address))?)
+    }
+
+    fn get_latest_blockhash(&self) -> anyhow::Result<Hash> {
+        let mut banks_client = self.context.banks_client.clone();
+        Ok(block_on(banks_client.get_latest_blockhash())?)
+    }
+
+    fn send_transaction(&self, transaction: &Transaction) -> anyhow::Result<Signature> {
+        let mut banks_client = self.context.banks_client.clone();
+        block_on(banks_client.process_transaction(transaction.clone()))?;
+        Ok(transaction.signatures[0])
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    tokio::task::block_in_place(|| Handle::current().block_on(future))
+}
+
+struct WatchingCluster<'a> {
+    simulator: &'a CrankSimulator,
+    entry:     WatchEntry,
+}
+
+impl Cluster for WatchingCluster<'_> {
+    fn get_account(&self, address: &Pubkey) -> anyhow::Result<Option<Account>> {
+        Cluster::get_account(self.simulator, address)
+    }
+
+    fn get_latest_blockhash(&self) -> anyhow::Result<Hash> {
+        Cluster::get_latest_blockhash(self.simulator)
+    }
+
+    fn send_transaction(&self, transaction: &Transaction) -> anyhow::Result<Signature> {
+        let mut watchlist = self.simulator.get_watchlist();
+        watchlist.watch(self.entry.clone());
+        watchlist.save().unwrap();
+        Cluster::send_transaction(self.simulator, transaction)
+    }
+}
@@ -0,0 +1,168 @@
+use {
+    crate::{
+        tests::simulator::CrankSimulator,
+        CrankEvent,
+        WatchEntry,
+        Watchlist,
+        INITIAL_BACKOFF,
+        MAX_BACKOFF,
+    },
+    solana_sdk::{
+        pubkey::Pubkey,
+        signature::Keypair,
+    },
+};
+
+// The crank blocks on the bank from the test runtime
+#[tokio::test(flavor = "multi_thread")]
+async fn test_crank() {
+    let (mut simulator, authority_keypair_1) = CrankSimulator::new().await;
+    let authority_2 = Pubkey::new_unique();
+
+    simulator.warp_to_timestamp(1700000000).await;
+
+    // Watched before the commit and before the timestamp, nothing happens
+    simulator.watch(&authority_2, 1700000000 + 30);
+    assert!(simulator.crank().is_empty());
+    simulator
+        .commit(&authority_keypair_1, &authority_2, 1700000000 + 30)
+        .await
+        .unwrap();
+    assert!(simulator.crank().is_empty());
+
+    let timelock_authority =
+        program_authority_client::timelock_authority(&authority_2, 1700000000 + 30);
+    simulator
+        .check_program_authority_matches(&timelock_authority)
+        .await;
+    assert_eq!(
+        simulator.get_watchlist().get_entries(),
+        [WatchEntry::new(
+            simulator.get_program(),
+            authority_2,
+            1700000000 + 30
+        )]
+    );
+
+    simulator.warp_to_timestamp(1700000000 + 30).await;
+    assert!(matches!(
+        simulator.crank()[..],
+        [CrankEvent::Transferred { .. }]
+    ));
+    simulator
+        .check_program_authority_matches(&authority_2)
+        .await;
+    assert!(simulator.get_watchlist().get_entries().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_crank_backoff() {
+    let (mut simulator, authority_keypair_1) = CrankSimulator::new().await;
+    let authority_2 = Pubkey::new_unique();
+    let timelock_authority =
+        program_authority_client::timelock_authority(&authority_2, 1700000000 + 30);
+
+    simulator.warp_to_timestamp(1700000000).await;
+    simulator
+        .commit(&authority_keypair_1, &authority_2, 1700000000 + 30)
+        .await
+        .unwrap();
+    simulator.watch(&authority_2, 1700000000 + 30);
+    simulator.warp_to_timestamp(1700000000 + 30).await;
+
+    // A fee payer without lamports makes the transfer fail
+    assert!(matches!(
+        simulator.crank_with_fee_payer(&Keypair::new())[..],
+        [CrankEvent::Failed { .. }]
+    ));
+    let entry = simulator.get_watchlist().get_entries()[0].clone();
+    assert_eq!(entry.attempts, 1);
+    assert_eq!(entry.retry_at, 1700000000 + 30 + INITIAL_BACKOFF);
+
+    // Nothing is sent until the backoff has elapsed
+    simulator.crank();
+    simulator
+        .check_program_authority_matches(&timelock_authority)
+        .await;
+
+    simulator
+        .warp_to_timestamp(1700000000 + 30 + INITIAL_BACKOFF)
+        .await;
+    simulator.crank_with_fee_payer(&Keypair::new());
+    let entry = simulator.get_watchlist().get_entries()[0].clone();
+    assert_eq!(entry.attempts, 2);
+    assert_eq!(
+        entry.retry_at,
+        1700000000 + 30 + INITIAL_BACKOFF + 2 * INITIAL_BACKOFF
+    );
+
+    simulator.warp_to_timestamp(entry.retry_at).await;
+    simulator.crank();
+    simulator
+        .check_program_authority_matches(&authority_2)
+        .await;
+    assert!(simulator.get_watchlist().get_entries().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_crank_keeps_concurrent_watch() {
+    let (mut simulator, authority_keypair_1) = CrankSimulator::new().await;
+    let authority_2 = Pubkey::new_unique();
+
+    simulator.warp_to_timestamp(1700000000).await;
+    simulator
+        .commit(&authority_keypair_1, &authority_2, 1700000000 + 30)
+        .await
+        .unwrap();
+    simulator.watch(&authority_2, 1700000000 + 30);
+    simulator.warp_to_timestamp(1700000000 + 30).await;
+
+    // The handover watched during the pass outlives the one it transferred
+    let entry = WatchEntry::new(Pubkey::new_unique(), Pubkey::new_unique(), 1700000000 + 60);
+    assert!(matches!(
+        simulator.crank_while_watching(entry.clone())[..],
+        [CrankEvent::Transferred { .. }]
+    ));
+    simulator
+        .check_program_authority_matches(&authority_2)
+        .await;
+    assert_eq!(simulator.get_watchlist().get_entries(), [entry]);
+}
+
+#[test]
+fn test_watchlist() {
+    let path = std::env::temp_dir().join(format!(
+        "program-authority-crank-{}.json",
+        Pubkey::new_unique()
+    ));
+
+    // A missing file is an empty list
+    let mut watchlist = Watchlist::load(&path).unwrap();
+    assert!(watchlist.get_entries().is_empty());
+
+    let entry = WatchEntry::new(Pubkey::new_unique(), Pubkey::new_unique(), 1700000000);
+    watchlist.watch(entry.clone());
+    watchlist.watch(entry.clone());
+    assert_eq!(watchlist.get_entries(), [entry.clone()]);
+    assert!(watchlist.due(1700000000 - 1).is_empty());
+    assert_eq!(watchlist.due(1700000000), vec![entry.clone()]);
+
+    // The backoff doubles up to its maximum
+    for _ in 0..20 {
+        watchlist.record_failure(&entry, 1700000000);
+    }
+    assert_eq!(watchlist.get_entries()[0].attempts, 20);
+    assert_eq!(
+        watchlist.get_entries()[0].retry_at,
+        1700000000 + MAX_BACKOFF
+    );
+    assert!(watchlist.due(1700000000 + MAX_BACKOFF - 1).is_empty());
+
+    // Everything survives a restart
+    watchlist.save().unwrap();
+    let reloaded = Watchlist::load(&path).unwrap();
+    assert_eq!(reloaded.get_entries(), watchlist.get_entries());
+
+    watchlist.remove(&entry);
+    assert!(watchlist.get_entries().is_empty());
+}
@@ -0,0 +1,177 @@
+use {
+    anyhow::Result,
+    program_authority_client::{
+        deserialize_pubkey,
+        serialize_pubkey,
+    },
+    serde::{
+        Deserialize,
+        Serialize,
+    },
+    solana_sdk::{
+        instruction::Instruction,
+        pubkey::Pubkey,
+    },
+    std::path::{
+        Path,
+        PathBuf,
+    },
+};
+
+/// Delay before retrying a failed transfer, doubled on every failure up to `MAX_BACKOFF` seconds
+pub const INITIAL_BACKOFF: i64 = 10;
+pub const MAX_BACKOFF: i64 = 60 * 60;
+
+/// A timelocked program to transfer to `new_authority` once `timestamp` has passed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct WatchEntry {
+    #[serde(
+        serialize_with = "serialize_pubkey",
+        deserialize_with = "deserialize_pubkey"
+    )]
+    pub program:       Pubkey,
+    #[serde(
+        serialize_with = "serialize_pubkey",
+        deserialize_with = "deserialize_pubkey"
+    )]
+    pub new_authority: Pubkey,
+    pub timestamp:     i64,
+    /// Failed transfers so far
+    #[serde(default)]
+    pub attempts:      u32,
+    /// Cluster time before which a failed transfer isn't retried
+    #[serde(default)]
+    pub retry_at:      i64,
+}
+
+/// Where the upgrade authority of a watched program stands
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntryState {
+    /// Held by the timelock, the transfer can be sent
+    Locked,
+    /// Already held by the new authority
+    Transferred,
+    /// Held by someone else, for instance before the commit
+    Waiting,
+}
+
+impl WatchEntry {
+    pub fn new(program: Pubkey, new_authority: Pubkey, timestamp: i64) -> Self {
+        WatchEntry {
+            program,
+            new_authority,
+            timestamp,
+            attempts: 0,
+            retry_at: 0,
+        }
+    }
+
+    pub fn timelock_authority(&self) -> Pubkey {
+        program_authority_client::timelock_authority(&self.new_authority, self.timestamp)
+    }
+
+    pub fn state(&self, upgrade_authority: Option<Pubkey>) -> EntryState {
+        if upgrade_authority == Some(self.timelock_authority()) {
+            EntryState::Locked
+        } else if upgrade_authority == Some(self.new_authority) {
+            EntryState::Transferred
+        } else {
+            EntryState::Waiting
+        }
+    }
+
+    pub fn is_due(&self, now: i64) -> bool {
+        now >= self.timestamp && now >= self.retry_at
+    }
+
+    pub fn transfer_ix(&self) -> Instruction {
+        program_authority_client::transfer_ix(
+            &self.new_authority,
+            &self.program,
+            self.timestamp,
+            None,
+        )
+    }
+
+    fn same_handover(&self, other: &WatchEntry) -> bool {
+        self.program == other.program
+            && self.new_authority == other.new_authority
+            && self.timestamp == other.timestamp
+    }
+}
+
+/// Watched programs, kept in a JSON file so a restarted crank picks up where it stopped
+#[derive(Debug)]
+pub struct Watchlist {
+    path:    PathBuf,
+    entries: Vec<WatchEntry>,
+}
+
+impl Watchlist {
+    /// Reads the list from `path`, which doesn't need to exist yet
+    pub fn load(path: &Path) -> Result<Self> {
+        let entries = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => vec![],
+            Err(err) => return Err(err.into()),
+        };
+        Ok(Watchlist {
+            path: path.to_path_buf(),
+            entries,
+        })
+    }
+
+    /// Writes to a temporary file first so a crash never leaves a truncated list behind
+    pub fn save(&self) -> Result<()> {
+        let temporary_path = self.path.with_extension("tmp");
+        std::fs::write(
+            &temporary_path,
+            serde_json::to_string_pretty(&self.entries)?,
+        )?;
+        std::fs::rename(&temporary_path, &self.path)?;
+        Ok(())
+    }
+
+    pub fn get_entries(&self) -> &[WatchEntry] {
+        &self.entries
+    }
+
+    /// Adds a handover unless it is already watched
+    pub fn watch(&mut self, entry: WatchEntry) {
+        if !self
+            .entries
+            .iter()
+            .any(|watched| watched.same_handover(&entry))
+        {
+            self.entries.push(entry);
+        }
+    }
+
+    pub fn remove(&mut self, entry: &WatchEntry) {
+        self.entries.retain(|watched| !watched.same_handover(entry));
+    }
+
+    pub fn due(&self, now: i64) -> Vec<WatchEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.is_due(now))
+            .cloned()
+            .collect()
+    }
+
+    /// Pushes the next attempt back by `INITIAL_BACKOFF` seconds, doubled for every previous
+    /// failure up to `MAX_BACKOFF`
+    pub fn record_failure(&mut self, entry: &WatchEntry, now: i64) {
+        if let Some(watched) = self
+            .entries
+            .iter_mut()
+            .find(|watched| watched.same_handover(entry))
+        {
+            let backoff = INITIAL_BACKOFF
+                .saturating_mul(1 << watched.attempts.min(16))
+                .min(MAX_BACKOFF);
+            watched.attempts += 1;
+            watched.retry_at = now.saturating_add(backoff);
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use {
+    anyhow::Result,
+    program_authority_client::{
+        deserialize_optional_pubkey,
+        deserialize_pubkey,
+        deserialize_pubkeys,
+    },
+    serde::Deserialize,
+    solana_sdk::pubkey::Pubkey,
+    std::path::Path,
+};
+
+/// Programs to watch, read from a TOML file
+#[derive(Deserialize, Debug, Clone)]
+pub struct Config {
+    /// Keys taking part in handovers, to recognize the escrow and timelock addresses
+    #[serde(default, deserialize_with = "deserialize_pubkeys")]
+    pub candidates: Vec<Pubkey>,
+    /// Unlock timestamps to recognize the timelock addresses
+    #[serde(default)]
+    pub timestamps: Vec<i64>,
+    /// Nonces to recognize the scoped escrow addresses
+    #[serde(default)]
+    pub nonces:     Vec<u64>,
+    #[serde(rename = "program")]
+    pub programs:   Vec<MonitoredProgram>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct MonitoredProgram {
+    #[serde(deserialize_with = "deserialize_pubkey")]
+    pub address:            Pubkey,
+    /// Key that should hold the upgrade authority, any other key counts as drift
+    #[serde(default, deserialize_with = "deserialize_optional_pubkey")]
+    pub expected_authority: Option<Pubkey>,
+}
+
+impl Config {
+    pub fn parse(contents: &str) -> Result<Self> {
+        Ok(toml::from_str(contents)?)
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+}
@@ -0,0 +1,17 @@
+#![deny(warnings)]
+
+//! Polls the program data accounts of a list of programs, classifies their upgrade authorities
+//! with the escrow and timelock seeds and renders the result as Prometheus gauges.
+
+mod config;
+mod monitor;
+mod source;
+
+pub use {
+    config::*,
+    monitor::*,
+    source::*,
+};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,85 @@
+use {
+    anyhow::Result,
+    clap::Parser,
+    program_authority_monitor::{
+        Config,
+        Monitor,
+    },
+    solana_client::rpc_client::RpcClient,
+    solana_sdk::commitment_config::CommitmentConfig,
+    std::{
+        io::{
+            Read,
+            Write,
+        },
+        net::TcpListener,
+        path::PathBuf,
+        sync::{
+            Arc,
+            Mutex,
+        },
+        thread,
+        time::Duration,
+    },
+};
+
+#[derive(Parser, Debug)]
+#[clap(
+    name = "program-authority-monitor",
+    about = "Exports the state of program upgrade authorities as Prometheus gauges"
+)]
+struct Cli {
+    /// RPC URL of the cluster
+    #[clap(long, short = 'u', default_value = "http://localhost:8899")]
+    url:      String,
+    /// TOML file listing the programs to watch
+    #[clap(long)]
+    config:   PathBuf,
+    /// Address serving the metrics
+    #[clap(long, default_value = "127.0.0.1:9464")]
+    listen:   String,
+    /// Seconds between two polls
+    #[clap(long, default_value = "30")]
+    interval: u64,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let mut monitor = Monitor::new(Config::read(&cli.config)?);
+    let rpc_client = RpcClient::new_with_commitment(cli.url, CommitmentConfig::confirmed());
+
+    let metrics = Arc::new(Mutex::new(String::new()));
+    let listener = TcpListener::bind(&cli.listen)?;
+    {
+        let metrics = metrics.clone();
+        thread::spawn(move || serve(listener, metrics));
+    }
+
+    loop {
+        if let Err(err) = monitor.poll(&rpc_client) {
+            eprintln!("{err}");
+        }
+        *metrics.lock().unwrap() = monitor.render();
+        thread::sleep(Duration::from_secs(cli.interval));
+    }
+}
+
+/// Answers every request with the latest metrics, whatever its path
+fn serve(listener: TcpListener, metrics: Arc<Mutex<String>>) {
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(_) => continue,
+        };
+        let mut request = [0; 1024];
+        let _ = stream.read(&mut request);
+
+        let body = metrics.lock().unwrap().clone();
+        let _ = write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+    }
+}
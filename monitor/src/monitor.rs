@@ -0,0 +1,246 @@
+use {
+    crate::{
+        AccountSource,
+        Config,
+    },
+    anyhow::{
+        anyhow,
+        Result,
+    },
+    program_authority_common::AuthorityStatus,
+    solana_sdk::pubkey::Pubkey,
+    std::fmt::Write,
+};
+
+const STATES: [&str; 4] = ["direct", "escrowed", "timelocked", "immutable"];
+
+/// What the monitor knows about a program after the last poll
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProgramState {
+    /// `None` until the program data account has been read once
+    pub status:       Option<AuthorityStatus>,
+    /// When this process first saw the current escrow. Neither the escrow nor the program data
+    /// account record when the proposal was made, so it starts over when the monitor restarts
+    pub escrow_since: Option<i64>,
+    /// Whether the last read succeeded, the status is stale otherwise
+    pub up:           bool,
+}
+
+pub struct Monitor {
+    config: Config,
+    states: Vec<ProgramState>,
+    now:    i64,
+}
+
+impl Monitor {
+    pub fn new(config: Config) -> Self {
+        let states = vec![ProgramState::default(); config.programs.len()];
+        Monitor {
+            config,
+            states,
+            now: 0,
+        }
+    }
+
+    pub fn get_state(&self, program: &Pubkey) -> Option<&ProgramState> {
+        self.config
+            .programs
+            .iter()
+            .position(|monitored| monitored.address == *program)
+            .map(|index| &self.states[index])
+    }
+
+    /// Reads every program data account once. A program that can't be read keeps its last status
+    /// and is marked down
+    pub fn poll(&mut self, source: &impl AccountSource) -> Result<()> {
+        self.now = source.get_unix_timestamp()?;
+
+        for (program, state) in self.config.programs.iter().zip(self.states.iter_mut()) {
+            match read_status(source, &program.address, &self.config, self.now) {
+                Ok(status) => {
+                    state.escrow_since = if state_name(&status) != "escrowed" {
+                        None
+                    } else if state.status.as_ref() == Some(&status) {
+                        state.escrow_since
+                    } else {
+                        Some(self.now)
+                    };
+                    state.status = Some(status);
+                    state.up = true;
+                }
+                Err(_) => state.up = false,
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the program is held directly by another key than its expected authority, or is
+    /// immutable while it shouldn't be. Escrows and timelocks are handovers in progress
+    pub fn is_drifted(&self, program: &Pubkey) -> bool {
+        let expected_authority = self
+            .config
+            .programs
+            .iter()
+            .find(|monitored| monitored.address == *program)
+            .and_then(|monitored| monitored.expected_authority);
+        let status = self
+            .get_state(program)
+            .and_then(|state| state.status.as_ref());
+
+        match (expected_authority, status) {
+            (Some(expected_authority), Some(AuthorityStatus::Direct(upgrade_authority))) => {
+                *upgrade_authority != expected_authority
+            }
+            (Some(_), Some(AuthorityStatus::Immutable)) => true,
+            _ => false,
+        }
+    }
+
+    /// Gauges in the Prometheus text format
+    pub fn render(&self) -> String {
+        let mut metrics = String::new();
+
+        write_header(
+            &mut metrics,
+            "program_authority_up",
+            "1 when the last read of the program data account succeeded",
+        );
+        for (program, state) in self.config.programs.iter().zip(self.states.iter()) {
+            write_sample(
+                &mut metrics,
+                "program_authority_up",
+                &program.address,
+                None,
+                state.up as i64,
+            );
+        }
+
+        write_header(
+            &mut metrics,
+            "program_authority_state",
+            "1 for the kind of key holding the upgrade authority",
+        );
+        for (program, state) in self.config.programs.iter().zip(self.states.iter()) {
+            if let Some(status) = &state.status {
+                for name in STATES {
+                    write_sample(
+                        &mut metrics,
+                        "program_authority_state",
+                        &program.address,
+                        Some(name),
+                        (state_name(status) == name) as i64,
+                    );
+                }
+            }
+        }
+
+        write_header(
+            &mut metrics,
+            "program_authority_drift",
+            "1 when the program is held directly by another key than the expected authority",
+        );
+        for program in self.config.programs.iter() {
+            write_sample(
+                &mut metrics,
+                "program_authority_drift",
+                &program.address,
+                None,
+                self.is_drifted(&program.address) as i64,
+            );
+        }
+
+        write_header(
+            &mut metrics,
+            "escrow_pending_seconds",
+            "Seconds since the monitor first saw the program in escrow, reset when it restarts",
+        );
+        for (program, state) in self.config.programs.iter().zip(self.states.iter()) {
+            write_sample(
+                &mut metrics,
+                "escrow_pending_seconds",
+                &program.address,
+                None,
+                state
+                    .escrow_since
+                    .map_or(0, |escrow_since| self.now - escrow_since),
+            );
+        }
+
+        write_header(
+            &mut metrics,
+            "timelock_seconds_to_unlock",
+            "Seconds before the program can be transferred out of the timelock",
+        );
+        for (program, state) in self.config.programs.iter().zip(self.states.iter()) {
+            let seconds_to_unlock = match state.status {
+                Some(AuthorityStatus::Timelocked { timestamp, .. }) => {
+                    timestamp.saturating_sub(self.now).max(0)
+                }
+                _ => 0,
+            };
+            write_sample(
+                &mut metrics,
+                "timelock_seconds_to_unlock",
+                &program.address,
+                None,
+                seconds_to_unlock,
+            );
+        }
+
+        metrics
+    }
+}
+
+fn read_status(
+    source: &impl AccountSource,
+    program: &Pubkey,
+    config: &Config,
+    now: i64,
+) -> Result<AuthorityStatus> {
+    let data = source
+        .get_account_data(&program_authority_client::program_data_address(program))?
+        .ok_or_else(|| anyhow!("Program data account of {program} not found"))?;
+    let upgrade_authority = program_authority_client::decode_upgrade_authority(&data)?;
+    Ok(program_authority_client::inspect_authority(
+        program,
+        upgrade_authority,
+        &config.candidates,
+        &config.timestamps,
+        &config.nonces,
+        now,
+    ))
+}
+
+/// Pending immutability counts as an escrow, until the confirm authority signs
+fn state_name(status: &AuthorityStatus) -> &'static str {
+    match status {
+        AuthorityStatus::Direct(_) => "direct",
+        AuthorityStatus::Escrowed { .. }
+        | AuthorityStatus::ScopedEscrowed { .. }
+        | AuthorityStatus::PendingImmutable { .. } => "escrowed",
+        AuthorityStatus::Timelocked { .. } => "timelocked",
+        AuthorityStatus::Immutable => "immutable",
+    }
+}
+
+fn write_header(metrics: &mut String, name: &str, help: &str) {
+    writeln!(metrics, "# HELP {name} {help}").unwrap();
+    writeln!(metrics, "# TYPE {name} gauge").unwrap();
+}
+
+fn write_sample(
+    metrics: &mut String,
+    name: &str,
+    program: &Pubkey,
+    state: Option<&str>,
+    value: i64,
+) {
+    match state {
+        Some(state) => writeln!(
+            metrics,
+            "{name}{{program=\"{program}\",state=\"{state}\"}} {value}"
+        ),
+        None => writeln!(metrics, "{name}{{program=\"{program}\"}} {value}"),
+    }
+    .unwrap();
+}
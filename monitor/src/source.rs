@@ -0,0 +1,37 @@
+use {
+    anyhow::{
+        anyhow,
+        Result,
+    },
+    solana_client::rpc_client::RpcClient,
+    solana_sdk::{
+        account::from_account,
+        clock::Clock,
+        pubkey::Pubkey,
+        sysvar,
+    },
+};
+
+/// Where the monitor reads accounts and the time from
+pub trait AccountSource {
+    /// Data of the account, `None` if it doesn't exist
+    fn get_account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>>;
+
+    /// Unix timestamp of the cluster clock
+    fn get_unix_timestamp(&self) -> Result<i64>;
+}
+
+impl AccountSource for RpcClient {
+    fn get_account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .get_account_with_commitment(address, self.commitment())?
+            .value
+            .map(|account| account.data))
+    }
+
+    fn get_unix_timestamp(&self) -> Result<i64> {
+        let clock: Clock = from_account(&self.get_account(&sysvar::clock::id())?)
+            .ok_or_else(|| anyhow!("Failed to decode the clock sysvar"))?;
+        Ok(clock.unix_timestamp)
+    }
+}
@@ -0,0 +1,52 @@
+use {
+    crate::AccountSource,
+    anchor_lang::prelude::UpgradeableLoaderState,
+    anyhow::Result,
+    solana_sdk::pubkey::Pubkey,
+    std::{
+        cell::RefCell,
+        collections::HashMap,
+    },
+};
+
+/// Accounts and clock kept in memory, changed by the tests between two polls
+#[derive(Default)]
+pub struct MockAccountSource {
+    accounts:       RefCell<HashMap<Pubkey, Vec<u8>>>,
+    unix_timestamp: RefCell<i64>,
+}
+
+impl MockAccountSource {
+    /// Stores a program data account holding the given upgrade authority
+    pub fn set_upgrade_authority(&self, program: &Pubkey, upgrade_authority: Option<Pubkey>) {
+        let data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+            slot:                      1,
+            upgrade_authority_address: upgrade_authority,
+        })
+        .unwrap();
+        self.accounts.borrow_mut().insert(
+            program_authority_client::program_data_address(program),
+            data,
+        );
+    }
+
+    pub fn remove_program(&self, program: &Pubkey) {
+        self.accounts
+            .borrow_mut()
+            .remove(&program_authority_client::program_data_address(program));
+    }
+
+    pub fn warp_to_timestamp(&self, unix_timestamp: i64) {
+        *self.unix_timestamp.borrow_mut() = unix_timestamp;
+    }
+}
+
+impl AccountSource for MockAccountSource {
+    fn get_account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>> {
+        Ok(self.accounts.borrow().get(address).cloned())
+    }
+
+    fn get_unix_timestamp(&self) -> Result<i64> {
+        Ok(*self.unix_timestamp.borrow())
+    }
+}
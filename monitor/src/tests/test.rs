@@ -0,0 +1,158 @@
+use {
+    crate::{
+        tests::simulator::MockAccountSource,
+        Config,
+        Monitor,
+    },
+    program_authority_escrow::AuthorityStatus,
+    solana_sdk::pubkey::Pubkey,
+};
+
+fn config(
+    program_1: &Pubkey,
+    program_2: &Pubkey,
+    authority_1: &Pubkey,
+    authority_2: &Pubkey,
+) -> Config {
+    Config::parse(&format!(
+        r#"
+candidates = ["{authority_1}", "{authority_2}"]
+timestamps = [1700000030]
+nonces = [7]
+
+[[program]]
+address = "{program_1}"
+expected_authority = "{authority_1}"
+
+[[program]]
+address = "{program_2}"
+"#
+    ))
+    .unwrap()
+}
+
+#[test]
+fn test_config() {
+    let program_1 = Pubkey::new_unique();
+    let program_2 = Pubkey::new_unique();
+    let authority_1 = Pubkey::new_unique();
+    let authority_2 = Pubkey::new_unique();
+
+    let config = config(&program_1, &program_2, &authority_1, &authority_2);
+    assert_eq!(config.candidates, vec![authority_1, authority_2]);
+    assert_eq!(config.timestamps, vec![1700000030]);
+    assert_eq!(config.nonces, vec![7]);
+    assert_eq!(config.programs.len(), 2);
+    assert_eq!(config.programs[0].address, program_1);
+    assert_eq!(config.programs[0].expected_authority, Some(authority_1));
+    assert_eq!(config.programs[1].expected_authority, None);
+
+    assert!(Config::parse("[[program]]\naddress = \"not a key\"").is_err());
+}
+
+#[test]
+fn test_monitor() {
+    let program_1 = Pubkey::new_unique();
+    let program_2 = Pubkey::new_unique();
+    let authority_1 = Pubkey::new_unique();
+    let authority_2 = Pubkey::new_unique();
+
+    let source = MockAccountSource::default();
+    let mut monitor = Monitor::new(config(&program_1, &program_2, &authority_1, &authority_2));
+
+    // Nothing is reported about a program before it has been read
+    assert!(!monitor.render().contains("program_authority_state{"));
+
+    source.warp_to_timestamp(1700000000);
+    source.set_upgrade_authority(&program_1, Some(authority_1));
+    source.set_upgrade_authority(
+        &program_2,
+        Some(program_authority_client::timelock_authority(
+            &authority_1,
+            1700000030,
+        )),
+    );
+    monitor.poll(&source).unwrap();
+
+    let metrics = monitor.render();
+    assert!(metrics.contains(&format!(
+        "program_authority_up{{program=\"{program_1}\"}} 1"
+    )));
+    assert!(metrics.contains(&format!(
+        "program_authority_state{{program=\"{program_1}\",state=\"direct\"}} 1"
+    )));
+    assert!(metrics.contains(&format!(
+        "program_authority_state{{program=\"{program_1}\",state=\"escrowed\"}} 0"
+    )));
+    assert!(metrics.contains(&format!(
+        "program_authority_state{{program=\"{program_2}\",state=\"timelocked\"}} 1"
+    )));
+    assert!(metrics.contains(&format!(
+        "program_authority_drift{{program=\"{program_1}\"}} 0"
+    )));
+    assert!(metrics.contains(&format!(
+        "timelock_seconds_to_unlock{{program=\"{program_2}\"}} 30"
+    )));
+
+    // The escrow is timed from the first poll that sees it
+    source.set_upgrade_authority(
+        &program_1,
+        Some(program_authority_client::escrow_authority(
+            &authority_1,
+            &authority_2,
+        )),
+    );
+    monitor.poll(&source).unwrap();
+    source.warp_to_timestamp(1700000000 + 100);
+    monitor.poll(&source).unwrap();
+
+    assert_eq!(
+        monitor.get_state(&program_1).unwrap().status,
+        Some(AuthorityStatus::Escrowed {
+            current_authority: authority_1,
+            new_authority:     authority_2,
+        })
+    );
+    let metrics = monitor.render();
+    assert!(metrics.contains(&format!(
+        "escrow_pending_seconds{{program=\"{program_1}\"}} 100"
+    )));
+    assert!(metrics.contains(&format!(
+        "program_authority_drift{{program=\"{program_1}\"}} 0"
+    )));
+    assert!(metrics.contains(&format!(
+        "timelock_seconds_to_unlock{{program=\"{program_2}\"}} 0"
+    )));
+
+    // Any other key than the expected authority is drift, the escrow timer stops
+    let unknown_authority = Pubkey::new_unique();
+    source.set_upgrade_authority(&program_1, Some(unknown_authority));
+    source.remove_program(&program_2);
+    monitor.poll(&source).unwrap();
+
+    let metrics = monitor.render();
+    assert!(monitor.is_drifted(&program_1));
+    assert!(metrics.contains(&format!(
+        "program_authority_drift{{program=\"{program_1}\"}} 1"
+    )));
+    assert!(metrics.contains(&format!(
+        "escrow_pending_seconds{{program=\"{program_1}\"}} 0"
+    )));
+
+    // A program that can't be read is down and keeps its last status
+    assert!(metrics.contains(&format!(
+        "program_authority_up{{program=\"{program_2}\"}} 0"
+    )));
+    assert!(matches!(
+        monitor.get_state(&program_2).unwrap().status,
+        Some(AuthorityStatus::Timelocked { .. })
+    ));
+
+    source.set_upgrade_authority(&program_1, None);
+    monitor.poll(&source).unwrap();
+    assert!(monitor.render().contains(&format!(
+        "program_authority_state{{program=\"{program_1}\",state=\"immutable\"}} 1"
+    )));
+    assert!(monitor.is_drifted(&program_1));
+    assert!(!monitor.is_drifted(&program_2));
+}
@@ -0,0 +1,88 @@
+#![deny(warnings)]
+#![allow(clippy::result_large_err)]
+
+use {
+    anchor_lang::prelude::*,
+    program_authority_escrow::{
+        program::ProgramAuthorityEscrow,
+        BpfUpgradableLoader,
+    },
+    program_authority_timelock::program::ProgramAuthorityTimelock,
+};
+
+#[cfg(test)]
+mod tests;
+
+declare_id!("DEq1fnPt9u4VuQNgsnQSMNNBEr5EkmW1rosMjGx1z3y7");
+pub const AUTHORITY_SEED: &[u8] = b"authority";
+
+/// Shows a PDA of another program acting as the new authority of the escrow and the timelock.
+/// A real program would gate these instructions behind its own access control.
+#[program]
+pub mod program_authority_cpi_example {
+    use super::*;
+
+    pub fn accept(ctx: Context<Accept>) -> Result<()> {
+        program_authority_escrow::cpi::accept(CpiContext::new_with_signer(
+            ctx.accounts.escrow_program.to_account_info(),
+            program_authority_escrow::cpi::accounts::Accept {
+                current_authority:     ctx.accounts.current_authority.to_account_info(),
+                new_authority:         ctx.accounts.authority.to_account_info(),
+                escrow_authority:      ctx.accounts.escrow_authority.to_account_info(),
+                program_account:       ctx.accounts.program_account.to_account_info(),
+                program_data:          ctx.accounts.program_data.to_account_info(),
+                bpf_upgradable_loader: ctx.accounts.bpf_upgradable_loader.to_account_info(),
+            },
+            &[&[AUTHORITY_SEED, &[*ctx.bumps.get("authority").unwrap()]]],
+        ))
+    }
+
+    pub fn transfer(ctx: Context<Transfer>, timestamp: i64) -> Result<()> {
+        program_authority_timelock::cpi::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.timelock_program.to_account_info(),
+                program_authority_timelock::cpi::accounts::Transfer {
+                    new_authority:         ctx.accounts.authority.to_account_info(),
+                    escrow_authority:      ctx.accounts.escrow_authority.to_account_info(),
+                    program_account:       ctx.accounts.program_account.to_account_info(),
+                    program_data:          ctx.accounts.program_data.to_account_info(),
+                    bpf_upgradable_loader: ctx.accounts.bpf_upgradable_loader.to_account_info(),
+                },
+                &[&[AUTHORITY_SEED, &[*ctx.bumps.get("authority").unwrap()]]],
+            ),
+            timestamp,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct Accept<'info> {
+    /// CHECK: Checked by the escrow program
+    pub current_authority:     AccountInfo<'info>,
+    #[account(seeds = [AUTHORITY_SEED], bump)]
+    pub authority:             SystemAccount<'info>,
+    /// CHECK: Checked by the escrow program
+    pub escrow_authority:      AccountInfo<'info>,
+    /// CHECK: Checked by the escrow program
+    pub program_account:       AccountInfo<'info>,
+    /// CHECK: Checked by the escrow program
+    #[account(mut)]
+    pub program_data:          AccountInfo<'info>,
+    pub bpf_upgradable_loader: Program<'info, BpfUpgradableLoader>,
+    pub escrow_program:        Program<'info, ProgramAuthorityEscrow>,
+}
+
+#[derive(Accounts)]
+pub struct Transfer<'info> {
+    #[account(seeds = [AUTHORITY_SEED], bump)]
+    pub authority:             SystemAccount<'info>,
+    /// CHECK: Checked by the timelock program
+    pub escrow_authority:      AccountInfo<'info>,
+    /// CHECK: Checked by the timelock program
+    pub program_account:       AccountInfo<'info>,
+    /// CHECK: Checked by the timelock program
+    #[account(mut)]
+    pub program_data:          AccountInfo<'info>,
+    pub bpf_upgradable_loader: Program<'info, BpfUpgradableLoader>,
+    pub timelock_program:      Program<'info, ProgramAuthorityTimelock>,
+}
@@ -0,0 +1,2 @@
+mod simulator;
+mod test;
@@ -0,0 +1,275 @@
+use {
+    crate::AUTHORITY_SEED,
+    anchor_lang::{
+        prelude::{
+            Clock,
+            Pubkey,
+            Rent,
+            UpgradeableLoaderState,
+        },
+        AccountDeserialize,
+        InstructionData,
+        ProgramData,
+        ToAccountMetas,
+    },
+    solana_program_test::{
+        read_file,
+        BanksClientError,
+        ProgramTest,
+        ProgramTestContext,
+    },
+    solana_sdk::{
+        account::Account,
+        bpf_loader_upgradeable,
+        instruction::Instruction,
+        signature::{
+            Keypair,
+            Signer,
+        },
+        stake_history::Epoch,
+        transaction::Transaction,
+    },
+    std::path::PathBuf,
+};
+
+
+pub struct CpiExampleSimulator {
+    context:            ProgramTestContext,
+    helloworld_address: Pubkey,
+}
+
+impl CpiExampleSimulator {
+    /// Deploys the example program next to the escrow and the timelock
+    pub async fn new() -> (CpiExampleSimulator, Keypair) {
+        let mut bpf_data = read_file(PathBuf::from("../../tests/fixtures/helloworld.so"));
+
+        let mut program_test = ProgramTest::new("program_authority_cpi_example", crate::id(), None);
+        program_test.add_program(
+            "program_authority_escrow",
+            program_authority_escrow::id(),
+            None,
+        );
+        program_test.add_program(
+            "program_authority_timelock",
+            program_authority_timelock::id(),
+            None,
+        );
+        let upgrade_authority = Keypair::new();
+
+        let helloworld_address = add_program_as_upgradable(
+            &mut bpf_data,
+            &upgrade_authority.pubkey(),
+            &mut program_test,
+        );
+
+        let context = program_test.start_with_context().await;
+
+        (
+            CpiExampleSimulator {
+                context,
+                helloworld_address,
+            },
+            upgrade_authority,
+        )
+    }
+}
+
+pub fn add_program_as_upgradable(
+    data: &mut Vec<u8>,
+    upgrade_authority: &Pubkey,
+    program_test: &mut ProgramTest,
+) -> Pubkey {
+    let program_key = Pubkey::new_unique();
+    let (programdata_key, _) =
+        Pubkey::find_program_address(&[&program_key.to_bytes()], &bpf_loader_upgradeable::id());
+
+
+    let program_deserialized = UpgradeableLoaderState::Program {
+        programdata_address: programdata_key,
+    };
+    let programdata_deserialized = UpgradeableLoaderState::ProgramData {
+        slot:                      1,
+        upgrade_authority_address: Some(*upgrade_authority),
+    };
+
+    // Program contains a pointer to progradata
+    let program_vec = bincode::serialize(&program_deserialized).unwrap();
+    // Programdata contains a header and the binary of the program
+    let mut programdata_vec = bincode::serialize(&programdata_deserialized).unwrap();
+    programdata_vec.append(data);
+
+    let program_account = Account {
+        lamports:   Rent::default().minimum_balance(program_vec.len()),
+        data:       program_vec,
+        owner:      bpf_loader_upgradeable::ID,
+        executable: true,
+        rent_epoch: Epoch::default(),
+    };
+    let programdata_account = Account {
+        lamports:   Rent::default().minimum_balance(programdata_vec.len()),
+        data:       programdata_vec,
+        owner:      bpf_loader_upgradeable::ID,
+        executable: false,
+        rent_epoch: Epoch::default(),
+    };
+
+    // Add both accounts to program test, now the program is deployed as upgradable
+    program_test.add_account(program_key, program_account);
+    program_test.add_account(programdata_key, programdata_account);
+
+    program_key
+}
+
+
+impl CpiExampleSimulator {
+    async fn process_ix(
+        &mut self,
+        instruction: Instruction,
+        signers: &Vec<&Keypair>,
+    ) -> Result<(), BanksClientError> {
+        let mut transaction =
+            Transaction::new_with_payer(&[instruction], Some(&self.context.payer.pubkey()));
+
+        let blockhash = self
+            .context
+            .banks_client
+            .get_latest_blockhash()
+            .await
+            .unwrap();
+
+        transaction.partial_sign(&[&self.context.payer], blockhash);
+        transaction.partial_sign(signers, blockhash);
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+    }
+
+    pub async fn propose(
+        &mut self,
+        current_authority_keypair: &Keypair,
+        new_authority: &Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::propose_ix(
+            &current_authority_keypair.pubkey(),
+            new_authority,
+            &self.helloworld_address,
+            None,
+        );
+
+        self.process_ix(instruction, &vec![current_authority_keypair])
+            .await
+    }
+
+    pub async fn commit(
+        &mut self,
+        current_authority_keypair: &Keypair,
+        new_authority: &Pubkey,
+        timestamp: i64,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::commit_ix(
+            &current_authority_keypair.pubkey(),
+            new_authority,
+            &self.helloworld_address,
+            timestamp,
+            None,
+        );
+
+        self.process_ix(instruction, &vec![current_authority_keypair])
+            .await
+    }
+
+    /// Accepts the program from the escrow with the example PDA as the new authority
+    pub async fn cpi_accept(&mut self, current_authority: &Pubkey) -> Result<(), BanksClientError> {
+        let authority = self.get_authority();
+        let escrow_authority = Pubkey::find_program_address(
+            &[current_authority.as_ref(), authority.as_ref()],
+            &program_authority_escrow::id(),
+        )
+        .0;
+        let account_metas = crate::accounts::Accept {
+            current_authority: *current_authority,
+            authority,
+            escrow_authority,
+            program_account: self.helloworld_address,
+            program_data: self.get_program_data_address(),
+            bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+            escrow_program: program_authority_escrow::id(),
+        }
+        .to_account_metas(None);
+
+        let instruction = Instruction {
+            program_id: crate::id(),
+            accounts:   account_metas,
+            data:       crate::instruction::Accept.data(),
+        };
+
+        self.process_ix(instruction, &vec![]).await
+    }
+
+    /// Transfers the program out of the timelock to the example PDA
+    pub async fn cpi_transfer(&mut self, timestamp: i64) -> Result<(), BanksClientError> {
+        let authority = self.get_authority();
+        let account_metas = crate::accounts::Transfer {
+            authority,
+            escrow_authority: self.get_timelock_authority(&authority, timestamp),
+            program_account: self.helloworld_address,
+            program_data: self.get_program_data_address(),
+            bpf_upgradable_loader: bpf_loader_upgradeable::id(),
+            timelock_program: program_authority_timelock::id(),
+        }
+        .to_account_metas(None);
+
+        let instruction = Instruction {
+            program_id: crate::id(),
+            accounts:   account_metas,
+            data:       crate::instruction::Transfer { timestamp }.data(),
+        };
+
+        self.process_ix(instruction, &vec![]).await
+    }
+
+    pub fn get_authority(&self) -> Pubkey {
+        Pubkey::find_program_address(&[AUTHORITY_SEED], &crate::id()).0
+    }
+
+    pub fn get_timelock_authority(&self, new_authority: &Pubkey, timestamp: i64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[new_authority.as_ref(), timestamp.to_be_bytes().as_ref()],
+            &program_authority_timelock::id(),
+        )
+        .0
+    }
+
+    pub fn get_program_data_address(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[self.helloworld_address.as_ref()],
+            &bpf_loader_upgradeable::id(),
+        )
+        .0
+    }
+
+    pub async fn get_program_data(&mut self) -> ProgramData {
+        let account = self
+            .context
+            .banks_client
+            .get_account(self.get_program_data_address())
+            .await
+            .unwrap()
+            .unwrap();
+        ProgramData::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub async fn warp_to_timestamp(&mut self, timestamp: i64) {
+        let current_clock = self
+            .context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap();
+        self.context.set_sysvar::<Clock>(&Clock {
+            unix_timestamp: timestamp,
+            ..current_clock
+        });
+    }
+}
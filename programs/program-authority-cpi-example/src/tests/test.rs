@@ -0,0 +1,100 @@
+use {
+    crate::tests::simulator::CpiExampleSimulator,
+    anchor_lang::prelude::{
+        ProgramError,
+        Pubkey,
+    },
+    solana_sdk::{
+        instruction::InstructionError,
+        signature::Keypair,
+        signer::Signer,
+        transaction::TransactionError,
+    },
+};
+
+fn into_transaction_error<T: Into<anchor_lang::prelude::Error>>(error: T) -> TransactionError {
+    TransactionError::InstructionError(
+        0,
+        InstructionError::try_from(u64::from(ProgramError::from(error.into()))).unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn test_cpi_accept() {
+    let (mut simulator, authority_keypair_1) = CpiExampleSimulator::new().await;
+    let authority = simulator.get_authority();
+
+    simulator
+        .propose(&authority_keypair_1, &authority)
+        .await
+        .unwrap();
+    simulator
+        .cpi_accept(&authority_keypair_1.pubkey())
+        .await
+        .unwrap();
+
+    let program_data = simulator.get_program_data().await;
+    assert_eq!(program_data.upgrade_authority_address, Some(authority));
+}
+
+#[tokio::test]
+async fn test_cpi_accept_wrong_new_authority() {
+    let (mut simulator, authority_keypair_1) = CpiExampleSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+
+    // The program was proposed to someone else, the PDA can't accept it
+    simulator
+        .propose(&authority_keypair_1, &authority_keypair_2.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(
+        simulator
+            .cpi_accept(&authority_keypair_1.pubkey())
+            .await
+            .unwrap_err()
+            .unwrap(),
+        into_transaction_error(program_authority_escrow::ErrorCode::ProgramNotEscrowed)
+    );
+
+    let program_data = simulator.get_program_data().await;
+    assert_eq!(
+        program_data.upgrade_authority_address,
+        Some(
+            Pubkey::find_program_address(
+                &[
+                    authority_keypair_1.pubkey().as_ref(),
+                    authority_keypair_2.pubkey().as_ref(),
+                ],
+                &program_authority_escrow::id(),
+            )
+            .0
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_cpi_transfer() {
+    let (mut simulator, authority_keypair_1) = CpiExampleSimulator::new().await;
+    let authority = simulator.get_authority();
+
+    simulator.warp_to_timestamp(1700000000).await;
+    simulator
+        .commit(&authority_keypair_1, &authority, 1700000000 + 30)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        simulator
+            .cpi_transfer(1700000000 + 30)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        into_transaction_error(program_authority_timelock::ErrorCode::TimestampTooEarly)
+    );
+
+    simulator.warp_to_timestamp(1700000000 + 31).await;
+    simulator.cpi_transfer(1700000000 + 30).await.unwrap();
+
+    let program_data = simulator.get_program_data().await;
+    assert_eq!(program_data.upgrade_authority_address, Some(authority));
+}
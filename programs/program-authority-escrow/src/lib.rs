@@ -1,31 +1,80 @@
 #![deny(warnings)]
 #![allow(clippy::result_large_err)]
 
-use anchor_lang::{
-    prelude::*,
-    solana_program::{
-        bpf_loader_upgradeable,
-        program::{
-            invoke,
-            invoke_signed,
+pub use program_authority_common::{
+    find_authority_status,
+    AuthorityStatus,
+    HandoverMemo,
+    IMMUTABLE_SEED,
+    MAX_REASON_LEN,
+    MAX_STATUS_CANDIDATES,
+    MAX_STATUS_NONCES,
+    MAX_STATUS_TIMESTAMPS,
+    STATUS_COMPUTE_UNIT_LIMIT,
+};
+use {
+    anchor_lang::{
+        prelude::*,
+        solana_program::{
+            bpf_loader_upgradeable,
+            program::{
+                invoke,
+                invoke_signed,
+            },
         },
     },
+    program_authority_common::{
+        find_unusable_authority,
+        is_strict_authority,
+        InvalidMemo,
+        UnusableAuthority,
+    },
+    program_authority_timelock::program::ProgramAuthorityTimelock,
 };
 
 #[cfg(test)]
 mod tests;
 
 declare_id!("escMHe7kSqPcDHx4HU44rAHhgdTLBZkUrU39aN8kMcL");
+pub const PROPOSAL_RECORD_SEED: &[u8] = b"proposal";
 
 #[program]
 pub mod program_authority_escrow {
     use super::*;
 
-    pub fn propose(ctx: Context<Propose>) -> Result<()> {
+    pub fn propose(ctx: Context<Propose>) -> Result<EscrowState> {
+        propose_handover(ctx, None)
+    }
+
+    pub fn propose_with_memo(ctx: Context<Propose>, memo: HandoverMemo) -> Result<EscrowState> {
+        propose_handover(ctx, Some(memo))
+    }
+
+    pub fn propose_strict(
+        ctx: Context<Propose>,
+        memo: Option<HandoverMemo>,
+    ) -> Result<EscrowState> {
+        check_strict_new_authority(&ctx.accounts.new_authority)?;
+        propose_handover(ctx, memo)
+    }
+
+    pub fn propose_with_record(
+        ctx: Context<ProposeWithRecord>,
+        memo: Option<HandoverMemo>,
+    ) -> Result<EscrowState> {
         let current_authority = &ctx.accounts.current_authority;
+        let new_authority = &ctx.accounts.new_authority;
         let escrow_authority = &ctx.accounts.escrow_authority;
         let program_account = &ctx.accounts.program_account;
 
+        check_upgrade_authority(
+            &ctx.accounts.program_data,
+            &current_authority.key(),
+            ErrorCode::NotCurrentAuthority,
+        )?;
+        check_new_authority(&current_authority.key(), &new_authority.key())?;
+        log_memo(&memo)?;
+
         invoke(
             &bpf_loader_upgradeable::set_upgrade_authority(
                 &program_account.key(),
@@ -35,45 +84,337 @@ pub mod program_authority_escrow {
             &ctx.accounts.to_account_infos(),
         )?;
 
+        emit_handover(
+            program_account.key(),
+            current_authority.key(),
+            escrow_authority.key(),
+            memo.clone(),
+        );
+
+        ctx.accounts.proposal_record.set_inner(ProposalRecord {
+            current_authority: current_authority.key(),
+            new_authority: new_authority.key(),
+            program_account: program_account.key(),
+            rent_payer: ctx.accounts.payer.key(),
+            created_at: Clock::get()?.unix_timestamp,
+            memo,
+        });
+
+        Ok(EscrowState {
+            escrow_authority: escrow_authority.key(),
+            bump:             *ctx.bumps.get("escrow_authority").unwrap(),
+            program_data:     ctx.accounts.program_data.key(),
+        })
+    }
+
+    pub fn close_proposal_record(ctx: Context<CloseProposalRecord>) -> Result<()> {
+        // Anyone can close the record once the proposal was accepted or reverted,
+        // the rent always goes back to the recorded payer
+        if ctx.accounts.program_data.upgrade_authority_address
+            == Some(ctx.accounts.escrow_authority.key())
+        {
+            return Err(ErrorCode::ProposalStillPending.into());
+        }
         Ok(())
     }
 
     pub fn revert(ctx: Context<Propose>) -> Result<()> {
+        revert_handover(ctx, None)
+    }
+
+    pub fn revert_with_memo(ctx: Context<Propose>, memo: HandoverMemo) -> Result<()> {
+        revert_handover(ctx, Some(memo))
+    }
+
+    pub fn accept(ctx: Context<Accept>) -> Result<()> {
+        accept_handover(ctx, None)
+    }
+
+    pub fn accept_with_memo(ctx: Context<Accept>, memo: HandoverMemo) -> Result<()> {
+        accept_handover(ctx, Some(memo))
+    }
+
+    pub fn accept_checked(ctx: Context<Accept>, memo: Option<HandoverMemo>) -> Result<()> {
         let current_authority = &ctx.accounts.current_authority;
         let new_authority = &ctx.accounts.new_authority;
         let escrow_authority = &ctx.accounts.escrow_authority;
+        let program = &ctx.accounts.program_account;
+
+        check_upgrade_authority(
+            &ctx.accounts.program_data,
+            &escrow_authority.key(),
+            ErrorCode::ProgramNotEscrowed,
+        )?;
+        log_memo(&memo)?;
 
+        invoke_signed(
+            &bpf_loader_upgradeable::set_upgrade_authority_checked(
+                &program.key(),
+                &escrow_authority.key(),
+                &new_authority.key(),
+            ),
+            &ctx.accounts.to_account_infos(),
+            &[&[
+                current_authority.key().as_ref(),
+                new_authority.key().as_ref(),
+                &[*ctx.bumps.get("escrow_authority").unwrap()],
+            ]],
+        )?;
+
+        emit_handover(
+            program.key(),
+            escrow_authority.key(),
+            new_authority.key(),
+            memo,
+        );
+        Ok(())
+    }
+
+    pub fn accept_and_propose(
+        ctx: Context<AcceptAndPropose>,
+        memo: Option<HandoverMemo>,
+    ) -> Result<()> {
+        let current_authority = &ctx.accounts.current_authority;
+        let new_authority = &ctx.accounts.new_authority;
+        let escrow_authority = &ctx.accounts.escrow_authority;
+        let next_escrow_authority = &ctx.accounts.next_escrow_authority;
         let program = &ctx.accounts.program_account;
 
+        check_upgrade_authority(
+            &ctx.accounts.program_data,
+            &escrow_authority.key(),
+            ErrorCode::ProgramNotEscrowed,
+        )?;
+        check_new_authority(&new_authority.key(), &ctx.accounts.next_authority.key())?;
+        log_memo(&memo)?;
+
+        // The new authority never holds the program, it goes straight to the next escrow
         invoke_signed(
             &bpf_loader_upgradeable::set_upgrade_authority(
                 &program.key(),
                 &escrow_authority.key(),
+                Some(&next_escrow_authority.key()),
+            ),
+            &ctx.accounts.to_account_infos(),
+            &[&[
+                current_authority.key().as_ref(),
+                new_authority.key().as_ref(),
+                &[*ctx.bumps.get("escrow_authority").unwrap()],
+            ]],
+        )?;
+
+        emit_handover(
+            program.key(),
+            escrow_authority.key(),
+            next_escrow_authority.key(),
+            memo,
+        );
+        Ok(())
+    }
+
+    pub fn accept_to_timelock(
+        ctx: Context<AcceptToTimelock>,
+        timestamp: i64,
+        memo: Option<HandoverMemo>,
+    ) -> Result<()> {
+        let current_authority = &ctx.accounts.current_authority;
+        let new_authority = &ctx.accounts.new_authority;
+        let escrow_authority = &ctx.accounts.escrow_authority;
+        let timelock_authority = &ctx.accounts.timelock_authority;
+
+        check_upgrade_authority(
+            &ctx.accounts.program_data,
+            &escrow_authority.key(),
+            ErrorCode::ProgramNotEscrowed,
+        )?;
+        log_memo(&memo)?;
+
+        // The escrow authority commits the program to the timelock on behalf of the new authority
+        let current_authority_key = current_authority.key();
+        let new_authority_key = new_authority.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            current_authority_key.as_ref(),
+            new_authority_key.as_ref(),
+            &[*ctx.bumps.get("escrow_authority").unwrap()],
+        ]];
+        let cpi_context = CpiContext::new_with_signer(
+            ctx.accounts.timelock_program.to_account_info(),
+            program_authority_timelock::cpi::accounts::Commit {
+                current_authority:     escrow_authority.to_account_info(),
+                new_authority:         ctx.accounts.final_authority.to_account_info(),
+                escrow_authority:      timelock_authority.to_account_info(),
+                program_account:       ctx.accounts.program_account.to_account_info(),
+                program_data:          ctx.accounts.program_data.to_account_info(),
+                bpf_upgradable_loader: ctx.accounts.bpf_upgradable_loader.to_account_info(),
+            },
+            signer_seeds,
+        );
+        match memo.clone() {
+            Some(memo) => {
+                program_authority_timelock::cpi::commit_with_memo(cpi_context, timestamp, memo)?;
+            }
+            None => {
+                program_authority_timelock::cpi::commit(cpi_context, timestamp)?;
+            }
+        }
+
+        emit_handover(
+            ctx.accounts.program_account.key(),
+            escrow_authority.key(),
+            timelock_authority.key(),
+            memo,
+        );
+        Ok(())
+    }
+
+    pub fn propose_scoped(
+        ctx: Context<ProposeScoped>,
+        nonce: u64,
+        memo: Option<HandoverMemo>,
+    ) -> Result<EscrowState> {
+        let current_authority = &ctx.accounts.current_authority;
+        let escrow_authority = &ctx.accounts.escrow_authority;
+        let program_account = &ctx.accounts.program_account;
+
+        check_upgrade_authority(
+            &ctx.accounts.program_data,
+            &current_authority.key(),
+            ErrorCode::NotCurrentAuthority,
+        )?;
+        check_new_authority(&current_authority.key(), &ctx.accounts.new_authority.key())?;
+        log_memo(&memo)?;
+        // The nonce only goes into the seeds of the escrow authority, logged to find it back
+        msg!("Scoped to nonce {}", nonce);
+
+        invoke(
+            &bpf_loader_upgradeable::set_upgrade_authority(
+                &program_account.key(),
+                &current_authority.key(),
+                Some(&escrow_authority.key()),
+            ),
+            &ctx.accounts.to_account_infos(),
+        )?;
+
+        emit_handover(
+            program_account.key(),
+            current_authority.key(),
+            escrow_authority.key(),
+            memo,
+        );
+
+        Ok(EscrowState {
+            escrow_authority: escrow_authority.key(),
+            bump:             *ctx.bumps.get("escrow_authority").unwrap(),
+            program_data:     ctx.accounts.program_data.key(),
+        })
+    }
+
+    pub fn revert_scoped(
+        ctx: Context<ProposeScoped>,
+        nonce: u64,
+        memo: Option<HandoverMemo>,
+    ) -> Result<()> {
+        let current_authority = &ctx.accounts.current_authority;
+        let new_authority = &ctx.accounts.new_authority;
+        let escrow_authority = &ctx.accounts.escrow_authority;
+        let program_account = &ctx.accounts.program_account;
+
+        check_upgrade_authority(
+            &ctx.accounts.program_data,
+            &escrow_authority.key(),
+            ErrorCode::ProgramNotEscrowed,
+        )?;
+        log_memo(&memo)?;
+
+        invoke_signed(
+            &bpf_loader_upgradeable::set_upgrade_authority(
+                &program_account.key(),
+                &escrow_authority.key(),
                 Some(&current_authority.key()),
             ),
             &ctx.accounts.to_account_infos(),
             &[&[
                 current_authority.key().as_ref(),
                 new_authority.key().as_ref(),
+                program_account.key().as_ref(),
+                nonce.to_be_bytes().as_ref(),
                 &[*ctx.bumps.get("escrow_authority").unwrap()],
             ]],
         )?;
+
+        emit_handover(
+            program_account.key(),
+            escrow_authority.key(),
+            current_authority.key(),
+            memo,
+        );
         Ok(())
     }
 
-    pub fn accept(ctx: Context<Accept>) -> Result<()> {
+    pub fn accept_scoped(
+        ctx: Context<AcceptScoped>,
+        nonce: u64,
+        memo: Option<HandoverMemo>,
+    ) -> Result<()> {
         let current_authority = &ctx.accounts.current_authority;
         let new_authority = &ctx.accounts.new_authority;
         let escrow_authority = &ctx.accounts.escrow_authority;
-        let program = &ctx.accounts.program_account;
+        let program_account = &ctx.accounts.program_account;
+
+        check_upgrade_authority(
+            &ctx.accounts.program_data,
+            &escrow_authority.key(),
+            ErrorCode::ProgramNotEscrowed,
+        )?;
+        log_memo(&memo)?;
 
         invoke_signed(
             &bpf_loader_upgradeable::set_upgrade_authority(
-                &program.key(),
+                &program_account.key(),
                 &escrow_authority.key(),
                 Some(&new_authority.key()),
             ),
             &ctx.accounts.to_account_infos(),
+            &[&[
+                current_authority.key().as_ref(),
+                new_authority.key().as_ref(),
+                program_account.key().as_ref(),
+                nonce.to_be_bytes().as_ref(),
+                &[*ctx.bumps.get("escrow_authority").unwrap()],
+            ]],
+        )?;
+
+        emit_handover(
+            program_account.key(),
+            escrow_authority.key(),
+            new_authority.key(),
+            memo,
+        );
+        Ok(())
+    }
+
+    pub fn upgrade(ctx: Context<Upgrade>) -> Result<()> {
+        let current_authority = &ctx.accounts.current_authority;
+        let new_authority = &ctx.accounts.new_authority;
+        let escrow_authority = &ctx.accounts.escrow_authority;
+        let program_account = &ctx.accounts.program_account;
+        let buffer = &ctx.accounts.buffer;
+        let spill = &ctx.accounts.spill;
+
+        check_upgrade_authority(
+            &ctx.accounts.program_data,
+            &escrow_authority.key(),
+            ErrorCode::ProgramNotEscrowed,
+        )?;
+
+        invoke_signed(
+            &bpf_loader_upgradeable::upgrade(
+                &program_account.key(),
+                &buffer.key(),
+                &escrow_authority.key(),
+                &spill.key(),
+            ),
+            &ctx.accounts.to_account_infos(),
             &[&[
                 current_authority.key().as_ref(),
                 new_authority.key().as_ref(),
@@ -82,6 +423,357 @@ pub mod program_authority_escrow {
         )?;
         Ok(())
     }
+
+    pub fn propose_immutable(ctx: Context<ProposeImmutable>) -> Result<()> {
+        let current_authority = &ctx.accounts.current_authority;
+        let immutable_authority = &ctx.accounts.immutable_authority;
+        let program_account = &ctx.accounts.program_account;
+
+        check_upgrade_authority(
+            &ctx.accounts.program_data,
+            &current_authority.key(),
+            ErrorCode::NotCurrentAuthority,
+        )?;
+        check_new_authority(
+            &current_authority.key(),
+            &ctx.accounts.confirm_authority.key(),
+        )?;
+
+        invoke(
+            &bpf_loader_upgradeable::set_upgrade_authority(
+                &program_account.key(),
+                &current_authority.key(),
+                Some(&immutable_authority.key()),
+            ),
+            &ctx.accounts.to_account_infos(),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn revert_immutable(ctx: Context<ProposeImmutable>) -> Result<()> {
+        let current_authority = &ctx.accounts.current_authority;
+        let confirm_authority = &ctx.accounts.confirm_authority;
+        let immutable_authority = &ctx.accounts.immutable_authority;
+        let program_account = &ctx.accounts.program_account;
+
+        check_upgrade_authority(
+            &ctx.accounts.program_data,
+            &immutable_authority.key(),
+            ErrorCode::ProgramNotEscrowed,
+        )?;
+
+        invoke_signed(
+            &bpf_loader_upgradeable::set_upgrade_authority(
+                &program_account.key(),
+                &immutable_authority.key(),
+                Some(&current_authority.key()),
+            ),
+            &ctx.accounts.to_account_infos(),
+            &[&[
+                IMMUTABLE_SEED,
+                current_authority.key().as_ref(),
+                confirm_authority.key().as_ref(),
+                &[*ctx.bumps.get("immutable_authority").unwrap()],
+            ]],
+        )?;
+        Ok(())
+    }
+
+    pub fn confirm_immutable(ctx: Context<ConfirmImmutable>) -> Result<()> {
+        let current_authority = &ctx.accounts.current_authority;
+        let confirm_authority = &ctx.accounts.confirm_authority;
+        let immutable_authority = &ctx.accounts.immutable_authority;
+        let program_account = &ctx.accounts.program_account;
+
+        check_upgrade_authority(
+            &ctx.accounts.program_data,
+            &immutable_authority.key(),
+            ErrorCode::ProgramNotEscrowed,
+        )?;
+
+        invoke_signed(
+            &bpf_loader_upgradeable::set_upgrade_authority(
+                &program_account.key(),
+                &immutable_authority.key(),
+                None,
+            ),
+            &ctx.accounts.to_account_infos(),
+            &[&[
+                IMMUTABLE_SEED,
+                current_authority.key().as_ref(),
+                confirm_authority.key().as_ref(),
+                &[*ctx.bumps.get("immutable_authority").unwrap()],
+            ]],
+        )?;
+        Ok(())
+    }
+
+    pub fn status(
+        ctx: Context<Status>,
+        candidates: Vec<Pubkey>,
+        timestamps: Vec<i64>,
+        nonces: Vec<u64>,
+    ) -> Result<AuthorityStatus> {
+        if candidates.len() > MAX_STATUS_CANDIDATES
+            || timestamps.len() > MAX_STATUS_TIMESTAMPS
+            || nonces.len() > MAX_STATUS_NONCES
+        {
+            return Err(ErrorCode::TooManyStatusCandidates.into());
+        }
+
+        Ok(find_authority_status(
+            &ctx.accounts.program_account.key(),
+            ctx.accounts.program_data.upgrade_authority_address,
+            &candidates,
+            &timestamps,
+            &nonces,
+            Clock::get()?.unix_timestamp,
+        ))
+    }
+
+    pub fn assert_authority(
+        ctx: Context<AssertAuthority>,
+        expected_authority: Option<Pubkey>,
+    ) -> Result<()> {
+        let program_data = &ctx.accounts.program_data;
+
+        if program_data.upgrade_authority_address != expected_authority {
+            msg!(
+                "Expected upgrade authority {:?}, found {:?}",
+                expected_authority,
+                program_data.upgrade_authority_address
+            );
+            return Err(ErrorCode::UnexpectedAuthority.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn extend_program(ctx: Context<ExtendProgram>, additional_bytes: u32) -> Result<()> {
+        let escrow_authority = &ctx.accounts.escrow_authority;
+        let payer = &ctx.accounts.payer;
+        let program_account = &ctx.accounts.program_account;
+
+        check_upgrade_authority(
+            &ctx.accounts.program_data,
+            &escrow_authority.key(),
+            ErrorCode::ProgramNotEscrowed,
+        )?;
+
+        invoke(
+            &bpf_loader_upgradeable::extend_program(
+                &program_account.key(),
+                Some(&payer.key()),
+                additional_bytes,
+            ),
+            &ctx.accounts.to_account_infos(),
+        )?;
+        Ok(())
+    }
+}
+
+/// Returned by `propose` so callers don't have to derive the escrow authority themselves
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct EscrowState {
+    pub escrow_authority: Pubkey,
+    pub bump:             u8,
+    pub program_data:     Pubkey,
+}
+
+#[event]
+pub struct AuthorityHandover {
+    pub program_account: Pubkey,
+    pub from_authority:  Pubkey,
+    pub to_authority:    Pubkey,
+    pub memo:            Option<HandoverMemo>,
+}
+
+/// Created by `propose_with_record` and paid for by a separate payer, who gets the rent back on close
+#[account]
+pub struct ProposalRecord {
+    pub current_authority: Pubkey,
+    pub new_authority:     Pubkey,
+    pub program_account:   Pubkey,
+    pub rent_payer:        Pubkey,
+    pub created_at:        i64,
+    pub memo:              Option<HandoverMemo>,
+}
+
+impl ProposalRecord {
+    // The longest memo is a reason of `MAX_REASON_LEN` bytes
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 32 + 8 + 1 + 1 + 4 + MAX_REASON_LEN;
+}
+
+/// Shared by `propose`, `propose_with_memo` and `propose_strict`
+fn propose_handover(ctx: Context<Propose>, memo: Option<HandoverMemo>) -> Result<EscrowState> {
+    let current_authority = &ctx.accounts.current_authority;
+    let escrow_authority = &ctx.accounts.escrow_authority;
+    let program_account = &ctx.accounts.program_account;
+
+    check_upgrade_authority(
+        &ctx.accounts.program_data,
+        &current_authority.key(),
+        ErrorCode::NotCurrentAuthority,
+    )?;
+    check_new_authority(&current_authority.key(), &ctx.accounts.new_authority.key())?;
+    log_memo(&memo)?;
+
+    invoke(
+        &bpf_loader_upgradeable::set_upgrade_authority(
+            &program_account.key(),
+            &current_authority.key(),
+            Some(&escrow_authority.key()),
+        ),
+        &ctx.accounts.to_account_infos(),
+    )?;
+
+    emit_handover(
+        program_account.key(),
+        current_authority.key(),
+        escrow_authority.key(),
+        memo,
+    );
+
+    Ok(EscrowState {
+        escrow_authority: escrow_authority.key(),
+        bump:             *ctx.bumps.get("escrow_authority").unwrap(),
+        program_data:     ctx.accounts.program_data.key(),
+    })
+}
+
+/// Shared by `revert` and `revert_with_memo`
+fn revert_handover(ctx: Context<Propose>, memo: Option<HandoverMemo>) -> Result<()> {
+    let current_authority = &ctx.accounts.current_authority;
+    let new_authority = &ctx.accounts.new_authority;
+    let escrow_authority = &ctx.accounts.escrow_authority;
+
+    let program = &ctx.accounts.program_account;
+
+    check_upgrade_authority(
+        &ctx.accounts.program_data,
+        &escrow_authority.key(),
+        ErrorCode::ProgramNotEscrowed,
+    )?;
+    log_memo(&memo)?;
+
+    invoke_signed(
+        &bpf_loader_upgradeable::set_upgrade_authority(
+            &program.key(),
+            &escrow_authority.key(),
+            Some(&current_authority.key()),
+        ),
+        &ctx.accounts.to_account_infos(),
+        &[&[
+            current_authority.key().as_ref(),
+            new_authority.key().as_ref(),
+            &[*ctx.bumps.get("escrow_authority").unwrap()],
+        ]],
+    )?;
+
+    emit_handover(
+        program.key(),
+        escrow_authority.key(),
+        current_authority.key(),
+        memo,
+    );
+    Ok(())
+}
+
+/// Shared by `accept` and `accept_with_memo`
+fn accept_handover(ctx: Context<Accept>, memo: Option<HandoverMemo>) -> Result<()> {
+    let current_authority = &ctx.accounts.current_authority;
+    let new_authority = &ctx.accounts.new_authority;
+    let escrow_authority = &ctx.accounts.escrow_authority;
+    let program = &ctx.accounts.program_account;
+
+    check_upgrade_authority(
+        &ctx.accounts.program_data,
+        &escrow_authority.key(),
+        ErrorCode::ProgramNotEscrowed,
+    )?;
+    log_memo(&memo)?;
+
+    invoke_signed(
+        &bpf_loader_upgradeable::set_upgrade_authority(
+            &program.key(),
+            &escrow_authority.key(),
+            Some(&new_authority.key()),
+        ),
+        &ctx.accounts.to_account_infos(),
+        &[&[
+            current_authority.key().as_ref(),
+            new_authority.key().as_ref(),
+            &[*ctx.bumps.get("escrow_authority").unwrap()],
+        ]],
+    )?;
+
+    emit_handover(
+        program.key(),
+        escrow_authority.key(),
+        new_authority.key(),
+        memo,
+    );
+    Ok(())
+}
+
+/// Fails with a dedicated error before the loader CPI if `expected_authority` doesn't hold the program
+fn check_upgrade_authority(
+    program_data: &ProgramData,
+    expected_authority: &Pubkey,
+    error: ErrorCode,
+) -> Result<()> {
+    match program_data.upgrade_authority_address {
+        None => Err(ErrorCode::ProgramImmutable.into()),
+        Some(authority) if authority != *expected_authority => Err(error.into()),
+        Some(_) => Ok(()),
+    }
+}
+
+/// Emits the `AuthorityHandover` event of every handover
+fn emit_handover(
+    program_account: Pubkey,
+    from_authority: Pubkey,
+    to_authority: Pubkey,
+    memo: Option<HandoverMemo>,
+) {
+    emit!(AuthorityHandover {
+        program_account,
+        from_authority,
+        to_authority,
+        memo,
+    });
+}
+
+/// Rejects empty or oversized reasons and writes the memo to the program logs
+fn log_memo(memo: &Option<HandoverMemo>) -> Result<()> {
+    match program_authority_common::log_memo(memo) {
+        Err(InvalidMemo::EmptyReason) => Err(ErrorCode::EmptyReason.into()),
+        Err(InvalidMemo::ReasonTooLong) => Err(ErrorCode::ReasonTooLong.into()),
+        Ok(()) => Ok(()),
+    }
+}
+
+/// Same rules as the timelock, with the escrow's error codes
+fn check_new_authority(current_authority: &Pubkey, new_authority: &Pubkey) -> Result<()> {
+    match find_unusable_authority(current_authority, new_authority, &crate::ID) {
+        Some(UnusableAuthority::SystemProgram) => {
+            Err(ErrorCode::NewAuthorityIsSystemProgram.into())
+        }
+        Some(UnusableAuthority::HandoverProgram) => {
+            Err(ErrorCode::NewAuthorityIsEscrowProgram.into())
+        }
+        Some(UnusableAuthority::SameAuthority) => Err(ErrorCode::SameAuthority.into()),
+        None => Ok(()),
+    }
+}
+
+fn check_strict_new_authority(new_authority: &AccountInfo) -> Result<()> {
+    if is_strict_authority(new_authority) {
+        Ok(())
+    } else {
+        Err(ErrorCode::NewAuthorityNotAllowed.into())
+    }
 }
 
 #[derive(Accounts)]
@@ -98,6 +790,40 @@ pub struct Propose<'info> {
     pub bpf_upgradable_loader: Program<'info, BpfUpgradableLoader>,
 }
 
+#[derive(Accounts)]
+pub struct ProposeWithRecord<'info> {
+    pub current_authority:     Signer<'info>,
+    /// CHECK: Unchecked new authority, can be a native wallet or a PDA of another program
+    pub new_authority:         AccountInfo<'info>,
+    #[account(seeds = [current_authority.key().as_ref(),new_authority.key().as_ref()], bump)]
+    pub escrow_authority:      SystemAccount<'info>,
+    #[account(init, payer = payer, space = ProposalRecord::LEN, seeds = [PROPOSAL_RECORD_SEED, escrow_authority.key().as_ref(), program_account.key().as_ref()], bump)]
+    pub proposal_record:       Account<'info, ProposalRecord>,
+    #[account(mut)]
+    pub payer:                 Signer<'info>,
+    #[account(executable, constraint = matches!(program_account.as_ref(), UpgradeableLoaderState::Program{..}))]
+    pub program_account:       Account<'info, UpgradeableLoaderState>,
+    #[account(mut, seeds = [program_account.key().as_ref()], bump, seeds::program = bpf_upgradable_loader.key())]
+    pub program_data:          Account<'info, ProgramData>,
+    pub system_program:        Program<'info, System>,
+    pub bpf_upgradable_loader: Program<'info, BpfUpgradableLoader>,
+}
+
+#[derive(Accounts)]
+pub struct CloseProposalRecord<'info> {
+    #[account(mut, close = rent_payer, has_one = rent_payer, has_one = program_account, seeds = [PROPOSAL_RECORD_SEED, escrow_authority.key().as_ref(), program_account.key().as_ref()], bump)]
+    pub proposal_record:       Account<'info, ProposalRecord>,
+    /// CHECK: Has to be the payer recorded in the proposal record
+    #[account(mut)]
+    pub rent_payer:            AccountInfo<'info>,
+    #[account(seeds = [proposal_record.current_authority.as_ref(), proposal_record.new_authority.as_ref()], bump)]
+    pub escrow_authority:      SystemAccount<'info>,
+    pub program_account:       Account<'info, UpgradeableLoaderState>,
+    #[account(seeds = [program_account.key().as_ref()], bump, seeds::program = bpf_upgradable_loader.key())]
+    pub program_data:          Account<'info, ProgramData>,
+    pub bpf_upgradable_loader: Program<'info, BpfUpgradableLoader>,
+}
+
 #[derive(Accounts)]
 pub struct Accept<'info> {
     /// CHECK: CPI will have the wrong seeds and fail if this is the wrong current authority
@@ -112,6 +838,158 @@ pub struct Accept<'info> {
     pub bpf_upgradable_loader: Program<'info, BpfUpgradableLoader>,
 }
 
+#[derive(Accounts)]
+pub struct AcceptAndPropose<'info> {
+    /// CHECK: CPI will have the wrong seeds and fail if this is the wrong current authority
+    pub current_authority:     AccountInfo<'info>,
+    pub new_authority:         Signer<'info>,
+    #[account(seeds = [current_authority.key().as_ref(),new_authority.key().as_ref()], bump)]
+    pub escrow_authority:      SystemAccount<'info>,
+    /// CHECK: Unchecked next authority, can be a native wallet or a PDA of another program
+    pub next_authority:        AccountInfo<'info>,
+    #[account(seeds = [new_authority.key().as_ref(),next_authority.key().as_ref()], bump)]
+    pub next_escrow_authority: SystemAccount<'info>,
+    #[account(executable, constraint = matches!(program_account.as_ref(), UpgradeableLoaderState::Program{..}))]
+    pub program_account:       Account<'info, UpgradeableLoaderState>,
+    #[account(mut, seeds = [program_account.key().as_ref()], bump, seeds::program = bpf_upgradable_loader.key())]
+    pub program_data:          Account<'info, ProgramData>,
+    pub bpf_upgradable_loader: Program<'info, BpfUpgradableLoader>,
+}
+
+#[derive(Accounts)]
+#[instruction(timestamp : i64)]
+pub struct AcceptToTimelock<'info> {
+    /// CHECK: CPI will have the wrong seeds and fail if this is the wrong current authority
+    pub current_authority:     AccountInfo<'info>,
+    pub new_authority:         Signer<'info>,
+    #[account(seeds = [current_authority.key().as_ref(),new_authority.key().as_ref()], bump)]
+    pub escrow_authority:      SystemAccount<'info>,
+    /// CHECK: Unchecked final authority, can be a native wallet or a PDA of another program
+    pub final_authority:       AccountInfo<'info>,
+    #[account(seeds = [final_authority.key().as_ref(), timestamp.to_be_bytes().as_ref()], bump, seeds::program = timelock_program.key())]
+    pub timelock_authority:    SystemAccount<'info>,
+    #[account(executable, constraint = matches!(program_account.as_ref(), UpgradeableLoaderState::Program{..}))]
+    pub program_account:       Account<'info, UpgradeableLoaderState>,
+    #[account(mut, seeds = [program_account.key().as_ref()], bump, seeds::program = bpf_upgradable_loader.key())]
+    pub program_data:          Account<'info, ProgramData>,
+    pub bpf_upgradable_loader: Program<'info, BpfUpgradableLoader>,
+    pub timelock_program:      Program<'info, ProgramAuthorityTimelock>,
+}
+
+#[derive(Accounts)]
+#[instruction(nonce : u64)]
+pub struct ProposeScoped<'info> {
+    pub current_authority:     Signer<'info>,
+    /// CHECK: Unchecked new authority, can be a native wallet or a PDA of another program
+    pub new_authority:         AccountInfo<'info>,
+    #[account(seeds = [current_authority.key().as_ref(), new_authority.key().as_ref(), program_account.key().as_ref(), nonce.to_be_bytes().as_ref()], bump)]
+    pub escrow_authority:      SystemAccount<'info>,
+    #[account(executable, constraint = matches!(program_account.as_ref(), UpgradeableLoaderState::Program{..}))]
+    pub program_account:       Account<'info, UpgradeableLoaderState>,
+    #[account(mut, seeds = [program_account.key().as_ref()], bump, seeds::program = bpf_upgradable_loader.key())]
+    pub program_data:          Account<'info, ProgramData>,
+    pub bpf_upgradable_loader: Program<'info, BpfUpgradableLoader>,
+}
+
+#[derive(Accounts)]
+#[instruction(nonce : u64)]
+pub struct AcceptScoped<'info> {
+    /// CHECK: CPI will have the wrong seeds and fail if this is the wrong current authority
+    pub current_authority:     AccountInfo<'info>,
+    pub new_authority:         Signer<'info>,
+    #[account(seeds = [current_authority.key().as_ref(), new_authority.key().as_ref(), program_account.key().as_ref(), nonce.to_be_bytes().as_ref()], bump)]
+    pub escrow_authority:      SystemAccount<'info>,
+    #[account(executable, constraint = matches!(program_account.as_ref(), UpgradeableLoaderState::Program{..}))]
+    pub program_account:       Account<'info, UpgradeableLoaderState>,
+    #[account(mut, seeds = [program_account.key().as_ref()], bump, seeds::program = bpf_upgradable_loader.key())]
+    pub program_data:          Account<'info, ProgramData>,
+    pub bpf_upgradable_loader: Program<'info, BpfUpgradableLoader>,
+}
+
+#[derive(Accounts)]
+pub struct Upgrade<'info> {
+    pub current_authority:     Signer<'info>,
+    /// CHECK: Unchecked new authority, only used to derive the escrow authority
+    pub new_authority:         AccountInfo<'info>,
+    #[account(seeds = [current_authority.key().as_ref(),new_authority.key().as_ref()], bump)]
+    pub escrow_authority:      SystemAccount<'info>,
+    #[account(mut, executable, constraint = matches!(program_account.as_ref(), UpgradeableLoaderState::Program{..}))]
+    pub program_account:       Account<'info, UpgradeableLoaderState>,
+    #[account(mut, seeds = [program_account.key().as_ref()], bump, seeds::program = bpf_upgradable_loader.key())]
+    pub program_data:          Account<'info, ProgramData>,
+    #[account(mut, constraint = matches!(buffer.as_ref(), UpgradeableLoaderState::Buffer{authority_address} if *authority_address == Some(escrow_authority.key())))]
+    pub buffer:                Account<'info, UpgradeableLoaderState>,
+    /// CHECK: Receives the lamports of the buffer, can be any account
+    #[account(mut)]
+    pub spill:                 AccountInfo<'info>,
+    pub rent:                  Sysvar<'info, Rent>,
+    pub clock:                 Sysvar<'info, Clock>,
+    pub bpf_upgradable_loader: Program<'info, BpfUpgradableLoader>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeImmutable<'info> {
+    pub current_authority:     Signer<'info>,
+    /// CHECK: Unchecked confirm authority, can be a native wallet or a PDA of another program
+    pub confirm_authority:     AccountInfo<'info>,
+    #[account(seeds = [IMMUTABLE_SEED, current_authority.key().as_ref(), confirm_authority.key().as_ref()], bump)]
+    pub immutable_authority:   SystemAccount<'info>,
+    #[account(executable, constraint = matches!(program_account.as_ref(), UpgradeableLoaderState::Program{..}))]
+    pub program_account:       Account<'info, UpgradeableLoaderState>,
+    #[account(mut, seeds = [program_account.key().as_ref()], bump, seeds::program = bpf_upgradable_loader.key())]
+    pub program_data:          Account<'info, ProgramData>,
+    pub bpf_upgradable_loader: Program<'info, BpfUpgradableLoader>,
+}
+
+#[derive(Accounts)]
+pub struct ConfirmImmutable<'info> {
+    /// CHECK: CPI will have the wrong seeds and fail if this is the wrong current authority
+    pub current_authority:     AccountInfo<'info>,
+    pub confirm_authority:     Signer<'info>,
+    #[account(seeds = [IMMUTABLE_SEED, current_authority.key().as_ref(), confirm_authority.key().as_ref()], bump)]
+    pub immutable_authority:   SystemAccount<'info>,
+    #[account(executable, constraint = matches!(program_account.as_ref(), UpgradeableLoaderState::Program{..}))]
+    pub program_account:       Account<'info, UpgradeableLoaderState>,
+    #[account(mut, seeds = [program_account.key().as_ref()], bump, seeds::program = bpf_upgradable_loader.key())]
+    pub program_data:          Account<'info, ProgramData>,
+    pub bpf_upgradable_loader: Program<'info, BpfUpgradableLoader>,
+}
+
+#[derive(Accounts)]
+pub struct Status<'info> {
+    #[account(executable, constraint = matches!(program_account.as_ref(), UpgradeableLoaderState::Program{..}))]
+    pub program_account:       Account<'info, UpgradeableLoaderState>,
+    #[account(seeds = [program_account.key().as_ref()], bump, seeds::program = bpf_upgradable_loader.key())]
+    pub program_data:          Account<'info, ProgramData>,
+    pub bpf_upgradable_loader: Program<'info, BpfUpgradableLoader>,
+}
+
+#[derive(Accounts)]
+pub struct AssertAuthority<'info> {
+    #[account(executable, constraint = matches!(program_account.as_ref(), UpgradeableLoaderState::Program{..}))]
+    pub program_account:       Account<'info, UpgradeableLoaderState>,
+    #[account(seeds = [program_account.key().as_ref()], bump, seeds::program = bpf_upgradable_loader.key())]
+    pub program_data:          Account<'info, ProgramData>,
+    pub bpf_upgradable_loader: Program<'info, BpfUpgradableLoader>,
+}
+
+#[derive(Accounts)]
+pub struct ExtendProgram<'info> {
+    pub current_authority:     Signer<'info>,
+    /// CHECK: Unchecked new authority, only used to derive the escrow authority
+    pub new_authority:         AccountInfo<'info>,
+    #[account(seeds = [current_authority.key().as_ref(),new_authority.key().as_ref()], bump)]
+    pub escrow_authority:      SystemAccount<'info>,
+    #[account(mut)]
+    pub payer:                 Signer<'info>,
+    #[account(mut, executable, constraint = matches!(program_account.as_ref(), UpgradeableLoaderState::Program{..}))]
+    pub program_account:       Account<'info, UpgradeableLoaderState>,
+    #[account(mut, seeds = [program_account.key().as_ref()], bump, seeds::program = bpf_upgradable_loader.key())]
+    pub program_data:          Account<'info, ProgramData>,
+    pub system_program:        Program<'info, System>,
+    pub bpf_upgradable_loader: Program<'info, BpfUpgradableLoader>,
+}
+
 #[derive(Clone)]
 pub struct BpfUpgradableLoader {}
 
@@ -120,3 +998,32 @@ impl Id for BpfUpgradableLoader {
         bpf_loader_upgradeable::id()
     }
 }
+
+#[error_code]
+#[derive(PartialEq, Eq)]
+pub enum ErrorCode {
+    #[msg("Upgrade authority does not match the expected authority")]
+    UnexpectedAuthority,
+    #[msg("Program is not held by the escrow authority")]
+    ProgramNotEscrowed,
+    #[msg("Signer is not the current upgrade authority")]
+    NotCurrentAuthority,
+    #[msg("Program is immutable")]
+    ProgramImmutable,
+    #[msg("New authority is the same as the current authority")]
+    SameAuthority,
+    #[msg("New authority can't be the system program")]
+    NewAuthorityIsSystemProgram,
+    #[msg("New authority can't be the escrow program")]
+    NewAuthorityIsEscrowProgram,
+    #[msg("New authority is neither on curve nor owned by an allowlisted program")]
+    NewAuthorityNotAllowed,
+    #[msg("Reason can't be empty")]
+    EmptyReason,
+    #[msg("Reason is longer than 200 bytes")]
+    ReasonTooLong,
+    #[msg("Program is still held by the escrow authority")]
+    ProposalStillPending,
+    #[msg("Too many candidates, timestamps or nonces to check")]
+    TooManyStatusCandidates,
+}
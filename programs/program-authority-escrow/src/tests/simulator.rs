@@ -1,4 +1,13 @@
 use {
+    crate::{
+        AuthorityStatus,
+        EscrowState,
+        HandoverMemo,
+        ProposalRecord,
+        IMMUTABLE_SEED,
+        PROPOSAL_RECORD_SEED,
+        STATUS_COMPUTE_UNIT_LIMIT,
+    },
     anchor_lang::{
         prelude::{
             Pubkey,
@@ -6,9 +15,8 @@ use {
             UpgradeableLoaderState,
         },
         AccountDeserialize,
-        InstructionData,
+        AnchorDeserialize,
         ProgramData,
-        ToAccountMetas,
     },
     solana_program_test::{
         read_file,
@@ -19,18 +27,25 @@ use {
     solana_sdk::{
         account::Account,
         bpf_loader_upgradeable,
+        compute_budget::ComputeBudgetInstruction,
         hash::Hash,
         instruction::Instruction,
+        native_token::LAMPORTS_PER_SOL,
+        program::MAX_RETURN_DATA,
         signature::{
             Keypair,
             Signer,
         },
         stake_history::Epoch,
+        system_instruction,
         transaction::Transaction,
+        transaction_context::TransactionReturnData,
     },
     std::path::PathBuf,
 };
 
+/// Keeps every buffer write transaction under the packet size limit
+const BUFFER_WRITE_CHUNK_SIZE: usize = 800;
 
 pub struct EscrowSimulator {
     banks_client:       BanksClient,
@@ -43,11 +58,24 @@ pub struct EscrowSimulator {
 impl EscrowSimulator {
     /// Deploys the executor program as upgradable
     pub async fn new() -> (EscrowSimulator, Keypair) {
+        Self::new_with_deactivated_features(&[]).await
+    }
+
+    /// Same as `new` but on a runtime where the given features are not active
+    pub async fn new_with_deactivated_features(features: &[Pubkey]) -> (EscrowSimulator, Keypair) {
         let mut bpf_data = read_file(PathBuf::from("../../tests/fixtures/helloworld.so"));
 
         let escrow_address = crate::id();
 
         let mut program_test = ProgramTest::new("program_authority_escrow", escrow_address, None);
+        program_test.add_program(
+            "program_authority_timelock",
+            program_authority_timelock::id(),
+            None,
+        );
+        for feature in features {
+            program_test.deactivate_feature(*feature);
+        }
         let upgrade_authority = Keypair::new();
 
         let helloworld_address = add_program_as_upgradable(
@@ -123,9 +151,17 @@ impl EscrowSimulator {
         &mut self,
         instruction: Instruction,
         signers: &Vec<&Keypair>,
+    ) -> Result<(), BanksClientError> {
+        self.process_ixs(&[instruction], signers).await
+    }
+
+    async fn process_ixs(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &Vec<&Keypair>,
     ) -> Result<(), BanksClientError> {
         let mut transaction =
-            Transaction::new_with_payer(&[instruction], Some(&self.genesis_keypair.pubkey()));
+            Transaction::new_with_payer(instructions, Some(&self.genesis_keypair.pubkey()));
 
         let blockhash = self.banks_client.get_latest_blockhash().await.unwrap();
         self.recent_blockhash = blockhash;
@@ -135,47 +171,172 @@ impl EscrowSimulator {
         self.banks_client.process_transaction(transaction).await
     }
 
+    async fn simulate_ix(
+        &mut self,
+        instruction: Instruction,
+        signers: &Vec<&Keypair>,
+    ) -> Result<Option<TransactionReturnData>, BanksClientError> {
+        Ok(self.simulate_ixs(&[instruction], signers).await?.0)
+    }
+
+    /// Simulates the instructions in one transaction, returns the return data and the compute
+    /// units consumed
+    async fn simulate_ixs(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &Vec<&Keypair>,
+    ) -> Result<(Option<TransactionReturnData>, u64), BanksClientError> {
+        let mut transaction =
+            Transaction::new_with_payer(instructions, Some(&self.genesis_keypair.pubkey()));
+
+        let blockhash = self.banks_client.get_latest_blockhash().await.unwrap();
+        self.recent_blockhash = blockhash;
+
+        transaction.partial_sign(&[&self.genesis_keypair], self.recent_blockhash);
+        transaction.partial_sign(signers, self.recent_blockhash);
+        let simulation = self.banks_client.simulate_transaction(transaction).await?;
+        simulation
+            .result
+            .unwrap()
+            .map_err(BanksClientError::TransactionError)?;
+        let simulation_details = simulation.simulation_details.unwrap();
+        Ok((
+            simulation_details.return_data,
+            simulation_details.units_consumed,
+        ))
+    }
+
     pub async fn propose(
         &mut self,
         current_authority_keypair: &Keypair,
         new_authority: &Pubkey,
     ) -> Result<(), BanksClientError> {
-        let account_metas = crate::accounts::Propose::create(
+        self.propose_with_memo(current_authority_keypair, new_authority, None)
+            .await
+    }
+
+    pub async fn propose_with_memo(
+        &mut self,
+        current_authority_keypair: &Keypair,
+        new_authority: &Pubkey,
+        memo: Option<HandoverMemo>,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::propose_ix(
             &current_authority_keypair.pubkey(),
             new_authority,
             &self.helloworld_address,
-            &self.escrow_address,
-        )
-        .to_account_metas(None);
+            memo,
+        );
 
-        let instruction = Instruction {
-            program_id: self.escrow_address,
-            accounts:   account_metas,
-            data:       crate::instruction::Propose.data(),
-        };
+        self.process_ix(instruction, &vec![current_authority_keypair])
+            .await
+    }
+
+    /// Simulates `propose` and decodes its return data without changing the bank
+    pub async fn simulate_propose(
+        &mut self,
+        current_authority_keypair: &Keypair,
+        new_authority: &Pubkey,
+    ) -> Result<EscrowState, BanksClientError> {
+        let instruction = program_authority_client::propose_ix(
+            &current_authority_keypair.pubkey(),
+            new_authority,
+            &self.helloworld_address,
+            None,
+        );
+
+        let return_data = self
+            .simulate_ix(instruction, &vec![current_authority_keypair])
+            .await?
+            .unwrap();
+        assert_eq!(return_data.program_id, self.escrow_address);
+        Ok(EscrowState::try_from_slice(&return_data.data).unwrap())
+    }
+
+    pub async fn propose_strict(
+        &mut self,
+        current_authority_keypair: &Keypair,
+        new_authority: &Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::propose_strict_ix(
+            &current_authority_keypair.pubkey(),
+            new_authority,
+            &self.helloworld_address,
+            None,
+        );
 
         self.process_ix(instruction, &vec![current_authority_keypair])
             .await
     }
 
-    pub async fn revert(
+    /// Creates a keypair funded by the genesis keypair, to pay rent separately from the fee payer
+    pub async fn create_payer(&mut self) -> Keypair {
+        let payer = Keypair::new();
+        let instruction = system_instruction::transfer(
+            &self.genesis_keypair.pubkey(),
+            &payer.pubkey(),
+            LAMPORTS_PER_SOL,
+        );
+        self.process_ix(instruction, &vec![]).await.unwrap();
+        payer
+    }
+
+    pub async fn propose_with_record(
         &mut self,
         current_authority_keypair: &Keypair,
         new_authority: &Pubkey,
+        payer_keypair: &Keypair,
+        memo: Option<HandoverMemo>,
     ) -> Result<(), BanksClientError> {
-        let account_metas = crate::accounts::Propose::create(
+        let instruction = program_authority_client::propose_with_record_ix(
             &current_authority_keypair.pubkey(),
             new_authority,
+            &payer_keypair.pubkey(),
             &self.helloworld_address,
-            &self.escrow_address,
-        )
-        .to_account_metas(None);
+            memo,
+        );
+
+        self.process_ix(instruction, &vec![current_authority_keypair, payer_keypair])
+            .await
+    }
 
-        let instruction = Instruction {
-            program_id: self.escrow_address,
-            accounts:   account_metas,
-            data:       crate::instruction::Revert.data(),
-        };
+    pub async fn close_proposal_record(
+        &mut self,
+        current_authority: &Pubkey,
+        new_authority: &Pubkey,
+        rent_payer: &Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::close_proposal_record_ix(
+            current_authority,
+            new_authority,
+            rent_payer,
+            &self.helloworld_address,
+        );
+
+        self.process_ix(instruction, &vec![]).await
+    }
+
+    pub async fn revert(
+        &mut self,
+        current_authority_keypair: &Keypair,
+        new_authority: &Pubkey,
+    ) -> Result<(), BanksClientError> {
+        self.revert_with_memo(current_authority_keypair, new_authority, None)
+            .await
+    }
+
+    pub async fn revert_with_memo(
+        &mut self,
+        current_authority_keypair: &Keypair,
+        new_authority: &Pubkey,
+        memo: Option<HandoverMemo>,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::revert_ix(
+            &current_authority_keypair.pubkey(),
+            new_authority,
+            &self.helloworld_address,
+            memo,
+        );
 
         self.process_ix(instruction, &vec![current_authority_keypair])
             .await
@@ -186,24 +347,423 @@ impl EscrowSimulator {
         current_authority: &Pubkey,
         new_authority_keypair: &Keypair,
     ) -> Result<(), BanksClientError> {
-        let account_metas = crate::accounts::Accept::create(
+        self.accept_with_memo(current_authority, new_authority_keypair, None)
+            .await
+    }
+
+    pub async fn accept_with_memo(
+        &mut self,
+        current_authority: &Pubkey,
+        new_authority_keypair: &Keypair,
+        memo: Option<HandoverMemo>,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::accept_ix(
             current_authority,
             &new_authority_keypair.pubkey(),
             &self.helloworld_address,
-            &self.escrow_address,
+            memo,
+        );
+
+        self.process_ix(instruction, &vec![new_authority_keypair])
+            .await
+    }
+
+    pub async fn accept_checked(
+        &mut self,
+        current_authority: &Pubkey,
+        new_authority_keypair: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::accept_checked_ix(
+            current_authority,
+            &new_authority_keypair.pubkey(),
+            &self.helloworld_address,
+            None,
+        );
+
+        self.process_ix(instruction, &vec![new_authority_keypair])
+            .await
+    }
+
+    pub async fn accept_and_propose(
+        &mut self,
+        current_authority: &Pubkey,
+        new_authority_keypair: &Keypair,
+        next_authority: &Pubkey,
+    ) -> Result<(), BanksClientError> {
+        self.accept_and_propose_with_memo(
+            current_authority,
+            new_authority_keypair,
+            next_authority,
+            None,
         )
-        .to_account_metas(None);
+        .await
+    }
 
-        let instruction = Instruction {
-            program_id: self.escrow_address,
-            accounts:   account_metas,
-            data:       crate::instruction::Accept.data(),
-        };
+    pub async fn accept_and_propose_with_memo(
+        &mut self,
+        current_authority: &Pubkey,
+        new_authority_keypair: &Keypair,
+        next_authority: &Pubkey,
+        memo: Option<HandoverMemo>,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::accept_and_propose_ix(
+            current_authority,
+            &new_authority_keypair.pubkey(),
+            next_authority,
+            &self.helloworld_address,
+            memo,
+        );
 
         self.process_ix(instruction, &vec![new_authority_keypair])
             .await
     }
 
+    pub async fn accept_to_timelock(
+        &mut self,
+        current_authority: &Pubkey,
+        new_authority_keypair: &Keypair,
+        final_authority: &Pubkey,
+        timestamp: i64,
+    ) -> Result<(), BanksClientError> {
+        self.accept_to_timelock_with_memo(
+            current_authority,
+            new_authority_keypair,
+            final_authority,
+            timestamp,
+            None,
+        )
+        .await
+    }
+
+    pub async fn accept_to_timelock_with_memo(
+        &mut self,
+        current_authority: &Pubkey,
+        new_authority_keypair: &Keypair,
+        final_authority: &Pubkey,
+        timestamp: i64,
+        memo: Option<HandoverMemo>,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::accept_to_timelock_ix(
+            current_authority,
+            &new_authority_keypair.pubkey(),
+            final_authority,
+            &self.helloworld_address,
+            timestamp,
+            memo,
+        );
+
+        self.process_ix(instruction, &vec![new_authority_keypair])
+            .await
+    }
+
+    pub async fn propose_scoped(
+        &mut self,
+        current_authority_keypair: &Keypair,
+        new_authority: &Pubkey,
+        nonce: u64,
+    ) -> Result<(), BanksClientError> {
+        self.propose_scoped_with_memo(current_authority_keypair, new_authority, nonce, None)
+            .await
+    }
+
+    pub async fn propose_scoped_with_memo(
+        &mut self,
+        current_authority_keypair: &Keypair,
+        new_authority: &Pubkey,
+        nonce: u64,
+        memo: Option<HandoverMemo>,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::propose_scoped_ix(
+            &current_authority_keypair.pubkey(),
+            new_authority,
+            &self.helloworld_address,
+            nonce,
+            memo,
+        );
+
+        self.process_ix(instruction, &vec![current_authority_keypair])
+            .await
+    }
+
+    pub async fn revert_scoped(
+        &mut self,
+        current_authority_keypair: &Keypair,
+        new_authority: &Pubkey,
+        nonce: u64,
+    ) -> Result<(), BanksClientError> {
+        self.revert_scoped_with_memo(current_authority_keypair, new_authority, nonce, None)
+            .await
+    }
+
+    pub async fn revert_scoped_with_memo(
+        &mut self,
+        current_authority_keypair: &Keypair,
+        new_authority: &Pubkey,
+        nonce: u64,
+        memo: Option<HandoverMemo>,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::revert_scoped_ix(
+            &current_authority_keypair.pubkey(),
+            new_authority,
+            &self.helloworld_address,
+            nonce,
+            memo,
+        );
+
+        self.process_ix(instruction, &vec![current_authority_keypair])
+            .await
+    }
+
+    pub async fn accept_scoped(
+        &mut self,
+        current_authority: &Pubkey,
+        new_authority_keypair: &Keypair,
+        nonce: u64,
+    ) -> Result<(), BanksClientError> {
+        self.accept_scoped_with_memo(current_authority, new_authority_keypair, nonce, None)
+            .await
+    }
+
+    pub async fn accept_scoped_with_memo(
+        &mut self,
+        current_authority: &Pubkey,
+        new_authority_keypair: &Keypair,
+        nonce: u64,
+        memo: Option<HandoverMemo>,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::accept_scoped_ix(
+            current_authority,
+            &new_authority_keypair.pubkey(),
+            &self.helloworld_address,
+            nonce,
+            memo,
+        );
+
+        self.process_ix(instruction, &vec![new_authority_keypair])
+            .await
+    }
+
+    pub async fn propose_immutable(
+        &mut self,
+        current_authority_keypair: &Keypair,
+        confirm_authority: &Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::propose_immutable_ix(
+            &current_authority_keypair.pubkey(),
+            confirm_authority,
+            &self.helloworld_address,
+        );
+
+        self.process_ix(instruction, &vec![current_authority_keypair])
+            .await
+    }
+
+    pub async fn revert_immutable(
+        &mut self,
+        current_authority_keypair: &Keypair,
+        confirm_authority: &Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::revert_immutable_ix(
+            &current_authority_keypair.pubkey(),
+            confirm_authority,
+            &self.helloworld_address,
+        );
+
+        self.process_ix(instruction, &vec![current_authority_keypair])
+            .await
+    }
+
+    pub async fn confirm_immutable(
+        &mut self,
+        current_authority: &Pubkey,
+        confirm_authority_keypair: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::confirm_immutable_ix(
+            current_authority,
+            &confirm_authority_keypair.pubkey(),
+            &self.helloworld_address,
+        );
+
+        self.process_ix(instruction, &vec![confirm_authority_keypair])
+            .await
+    }
+
+    pub async fn assert_authority(
+        &mut self,
+        expected_authority: Option<Pubkey>,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::assert_authority_ix(
+            &self.helloworld_address,
+            expected_authority,
+        );
+
+        self.process_ix(instruction, &vec![]).await
+    }
+
+    pub async fn upgrade(
+        &mut self,
+        current_authority_keypair: &Keypair,
+        new_authority: &Pubkey,
+        buffer: &Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::upgrade_ix(
+            &current_authority_keypair.pubkey(),
+            new_authority,
+            &self.helloworld_address,
+            buffer,
+            &self.genesis_keypair.pubkey(),
+        );
+
+        self.process_ix(instruction, &vec![current_authority_keypair])
+            .await
+    }
+
+    pub async fn extend_program(
+        &mut self,
+        current_authority_keypair: &Keypair,
+        new_authority: &Pubkey,
+        additional_bytes: u32,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::escrow_extend_program_ix(
+            &current_authority_keypair.pubkey(),
+            new_authority,
+            &self.genesis_keypair.pubkey(),
+            &self.helloworld_address,
+            additional_bytes,
+        );
+
+        self.process_ix(instruction, &vec![current_authority_keypair])
+            .await
+    }
+
+    /// Writes `data` to a new buffer and hands the buffer authority to `buffer_authority`
+    pub async fn write_buffer(
+        &mut self,
+        data: &[u8],
+        buffer_authority: &Pubkey,
+    ) -> Result<Pubkey, BanksClientError> {
+        let buffer_keypair = Keypair::new();
+        let writer_keypair = Keypair::new();
+        let rent = self.banks_client.get_rent().await.unwrap();
+
+        let create_instructions = bpf_loader_upgradeable::create_buffer(
+            &self.genesis_keypair.pubkey(),
+            &buffer_keypair.pubkey(),
+            &writer_keypair.pubkey(),
+            rent.minimum_balance(UpgradeableLoaderState::size_of_buffer(data.len())),
+            data.len(),
+        )
+        .unwrap();
+        self.process_ixs(&create_instructions, &vec![&buffer_keypair])
+            .await?;
+
+        for (i, chunk) in data.chunks(BUFFER_WRITE_CHUNK_SIZE).enumerate() {
+            self.process_ix(
+                bpf_loader_upgradeable::write(
+                    &buffer_keypair.pubkey(),
+                    &writer_keypair.pubkey(),
+                    (i * BUFFER_WRITE_CHUNK_SIZE) as u32,
+                    chunk.to_vec(),
+                ),
+                &vec![&writer_keypair],
+            )
+            .await?;
+        }
+
+        self.process_ix(
+            bpf_loader_upgradeable::set_buffer_authority(
+                &buffer_keypair.pubkey(),
+                &writer_keypair.pubkey(),
+                buffer_authority,
+            ),
+            &vec![&writer_keypair],
+        )
+        .await?;
+
+        Ok(buffer_keypair.pubkey())
+    }
+
+    /// Returns the program binary stored after the `ProgramData` header
+    pub async fn get_program_binary(&mut self) -> Vec<u8> {
+        let program_data = Pubkey::find_program_address(
+            &[self.helloworld_address.as_ref()],
+            &bpf_loader_upgradeable::id(),
+        )
+        .0;
+
+        let account = self
+            .banks_client
+            .get_account(program_data)
+            .await
+            .unwrap()
+            .unwrap();
+        account.data[UpgradeableLoaderState::size_of_programdata_metadata()..].to_vec()
+    }
+
+    /// Simulates `status` and decodes who controls the program
+    pub async fn status(
+        &mut self,
+        candidates: Vec<Pubkey>,
+        timestamps: Vec<i64>,
+        nonces: Vec<u64>,
+    ) -> Result<AuthorityStatus, BanksClientError> {
+        let instruction = self.status_instruction(candidates, timestamps, nonces);
+
+        // The bank trims the trailing zeros of return data, they are added back for Borsh
+        let mut data = self.simulate_ix(instruction, &vec![]).await?.unwrap().data;
+        data.resize(MAX_RETURN_DATA, 0);
+        Ok(AuthorityStatus::deserialize(&mut data.as_slice()).unwrap())
+    }
+
+    /// Same as `status` under a limit of `STATUS_COMPUTE_UNIT_LIMIT`, along with the compute units
+    /// it consumed
+    pub async fn status_with_units(
+        &mut self,
+        candidates: Vec<Pubkey>,
+        timestamps: Vec<i64>,
+        nonces: Vec<u64>,
+    ) -> Result<(AuthorityStatus, u64), BanksClientError> {
+        let instruction = self.status_instruction(candidates, timestamps, nonces);
+
+        let (return_data, units_consumed) = self
+            .simulate_ixs(
+                &[
+                    ComputeBudgetInstruction::set_compute_unit_limit(STATUS_COMPUTE_UNIT_LIMIT),
+                    instruction,
+                ],
+                &vec![],
+            )
+            .await?;
+        let mut data = return_data.unwrap().data;
+        data.resize(MAX_RETURN_DATA, 0);
+        Ok((
+            AuthorityStatus::deserialize(&mut data.as_slice()).unwrap(),
+            units_consumed,
+        ))
+    }
+
+    fn status_instruction(
+        &self,
+        candidates: Vec<Pubkey>,
+        timestamps: Vec<i64>,
+        nonces: Vec<u64>,
+    ) -> Instruction {
+        program_authority_client::status_ix(
+            &self.helloworld_address,
+            candidates,
+            timestamps,
+            nonces,
+        )
+    }
+
+    pub fn get_program_data_address(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[self.helloworld_address.as_ref()],
+            &bpf_loader_upgradeable::id(),
+        )
+        .0
+    }
+
     pub async fn get_program_data(&mut self) -> ProgramData {
         let program_data = Pubkey::find_program_address(
             &[self.helloworld_address.as_ref()],
@@ -231,60 +791,80 @@ impl EscrowSimulator {
         )
         .0
     }
-}
 
-impl crate::accounts::Propose {
-    pub fn create(
+    pub fn get_timelock_authority(&self, final_authority: &Pubkey, timestamp: i64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[final_authority.as_ref(), timestamp.to_be_bytes().as_ref()],
+            &program_authority_timelock::id(),
+        )
+        .0
+    }
+
+    pub fn get_scoped_escrow_authority(
+        &self,
         current_authority: &Pubkey,
         new_authority: &Pubkey,
-        program_account: &Pubkey,
-        escrow_address: &Pubkey,
-    ) -> Self {
-        let escrow_authority = Pubkey::find_program_address(
-            &[current_authority.as_ref(), new_authority.as_ref()],
-            escrow_address,
+        nonce: u64,
+    ) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                current_authority.as_ref(),
+                new_authority.as_ref(),
+                self.helloworld_address.as_ref(),
+                nonce.to_be_bytes().as_ref(),
+            ],
+            &self.escrow_address,
         )
-        .0;
-        let program_data = Pubkey::find_program_address(
-            &[program_account.as_ref()],
-            &bpf_loader_upgradeable::id(),
+        .0
+    }
+
+    pub fn get_immutable_authority(
+        &self,
+        current_authority: &Pubkey,
+        confirm_authority: &Pubkey,
+    ) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                IMMUTABLE_SEED,
+                current_authority.as_ref(),
+                confirm_authority.as_ref(),
+            ],
+            &self.escrow_address,
         )
-        .0;
-        crate::accounts::Propose {
-            current_authority: *current_authority,
-            new_authority: *new_authority,
-            escrow_authority,
-            program_account: *program_account,
-            program_data,
-            bpf_upgradable_loader: bpf_loader_upgradeable::id(),
-        }
+        .0
     }
-}
 
-impl crate::accounts::Accept {
-    pub fn create(
+    pub fn get_proposal_record_address(
+        &self,
         current_authority: &Pubkey,
         new_authority: &Pubkey,
-        program_account: &Pubkey,
-        escrow_address: &Pubkey,
-    ) -> Self {
-        let escrow_authority = Pubkey::find_program_address(
-            &[current_authority.as_ref(), new_authority.as_ref()],
-            escrow_address,
-        )
-        .0;
-        let program_data = Pubkey::find_program_address(
-            &[program_account.as_ref()],
-            &bpf_loader_upgradeable::id(),
+    ) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                PROPOSAL_RECORD_SEED,
+                self.get_escrow_authority(current_authority, new_authority)
+                    .as_ref(),
+                self.helloworld_address.as_ref(),
+            ],
+            &self.escrow_address,
         )
-        .0;
-        crate::accounts::Accept {
-            current_authority: *current_authority,
-            new_authority: *new_authority,
-            escrow_authority,
-            program_account: *program_account,
-            program_data,
-            bpf_upgradable_loader: bpf_loader_upgradeable::id(),
-        }
+        .0
+    }
+
+    pub async fn get_proposal_record(
+        &mut self,
+        current_authority: &Pubkey,
+        new_authority: &Pubkey,
+    ) -> Option<ProposalRecord> {
+        let address = self.get_proposal_record_address(current_authority, new_authority);
+        self.banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .map(|account| ProposalRecord::try_deserialize(&mut account.data.as_slice()).unwrap())
+    }
+
+    pub async fn get_balance(&mut self, address: &Pubkey) -> u64 {
+        self.banks_client.get_balance(*address).await.unwrap()
     }
 }
@@ -1,11 +1,46 @@
 use {
-    crate::tests::simulator::EscrowSimulator,
+    crate::{
+        tests::simulator::EscrowSimulator,
+        AuthorityStatus,
+        ErrorCode,
+        EscrowState,
+        HandoverMemo,
+        ProposalRecord,
+        MAX_REASON_LEN,
+        MAX_STATUS_CANDIDATES,
+        MAX_STATUS_NONCES,
+        MAX_STATUS_TIMESTAMPS,
+        STATUS_COMPUTE_UNIT_LIMIT,
+    },
+    anchor_lang::prelude::{
+        ProgramError,
+        Pubkey,
+        Rent,
+    },
+    solana_program_test::read_file,
     solana_sdk::{
+        feature_set::enable_bpf_loader_set_authority_checked_ix,
+        instruction::InstructionError,
+        native_token::LAMPORTS_PER_SOL,
         signature::Keypair,
         signer::Signer,
+        transaction::TransactionError,
     },
+    std::path::PathBuf,
 };
 
+impl From<ErrorCode> for TransactionError {
+    fn from(val: ErrorCode) -> Self {
+        TransactionError::InstructionError(
+            0,
+            InstructionError::try_from(u64::from(ProgramError::from(
+                anchor_lang::prelude::Error::from(val),
+            )))
+            .unwrap(),
+        )
+    }
+}
+
 #[tokio::test]
 async fn test() {
     let (mut simulator, authority_keypair_1) = EscrowSimulator::new().await;
@@ -112,3 +147,1095 @@ async fn test() {
         Some(authority_keypair_1.pubkey())
     );
 }
+
+#[tokio::test]
+async fn test_upgrade() {
+    let (mut simulator, authority_keypair_1) = EscrowSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+    let new_binary = read_file(PathBuf::from("../../tests/fixtures/helloworld_v2.so"));
+
+    simulator
+        .propose(&authority_keypair_1, &authority_keypair_2.pubkey())
+        .await
+        .unwrap();
+
+    let escrow_authority = simulator
+        .get_escrow_authority(&authority_keypair_1.pubkey(), &authority_keypair_2.pubkey());
+    let buffer = simulator
+        .write_buffer(&new_binary, &escrow_authority)
+        .await
+        .unwrap();
+
+    // Only the current authority can upgrade through the escrow
+    assert!(simulator
+        .upgrade(&authority_keypair_2, &authority_keypair_1.pubkey(), &buffer)
+        .await
+        .is_err());
+
+    simulator
+        .upgrade(&authority_keypair_1, &authority_keypair_2.pubkey(), &buffer)
+        .await
+        .unwrap();
+    assert_eq!(simulator.get_program_binary().await, new_binary);
+
+    // The program is still in escrow and can be accepted
+    let program_data = simulator.get_program_data().await;
+    assert_eq!(
+        program_data.upgrade_authority_address,
+        Some(escrow_authority)
+    );
+    simulator
+        .accept(&authority_keypair_1.pubkey(), &authority_keypair_2)
+        .await
+        .unwrap();
+
+    let program_data = simulator.get_program_data().await;
+    assert_eq!(
+        program_data.upgrade_authority_address,
+        Some(authority_keypair_2.pubkey())
+    );
+}
+
+#[tokio::test]
+async fn test_extend_program() {
+    let (mut simulator, authority_keypair_1) = EscrowSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+
+    // The program can only be extended through the escrow once it is in escrow
+    assert_eq!(
+        simulator
+            .extend_program(&authority_keypair_1, &authority_keypair_2.pubkey(), 1024)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::ProgramNotEscrowed.into()
+    );
+
+    simulator
+        .propose(&authority_keypair_1, &authority_keypair_2.pubkey())
+        .await
+        .unwrap();
+
+    let binary_len = simulator.get_program_binary().await.len();
+    simulator
+        .extend_program(&authority_keypair_1, &authority_keypair_2.pubkey(), 1024)
+        .await
+        .unwrap();
+    assert_eq!(
+        simulator.get_program_binary().await.len(),
+        binary_len + 1024
+    );
+
+    simulator
+        .accept(&authority_keypair_1.pubkey(), &authority_keypair_2)
+        .await
+        .unwrap();
+
+    let program_data = simulator.get_program_data().await;
+    assert_eq!(
+        program_data.upgrade_authority_address,
+        Some(authority_keypair_2.pubkey())
+    );
+}
+
+#[tokio::test]
+async fn test_immutable() {
+    let (mut simulator, authority_keypair_1) = EscrowSimulator::new().await;
+    let confirm_keypair = Keypair::new();
+
+    simulator
+        .propose_immutable(&authority_keypair_1, &confirm_keypair.pubkey())
+        .await
+        .unwrap();
+
+    let program_data = simulator.get_program_data().await;
+    assert_eq!(
+        program_data.upgrade_authority_address,
+        Some(
+            simulator
+                .get_immutable_authority(&authority_keypair_1.pubkey(), &confirm_keypair.pubkey())
+        )
+    );
+
+    simulator
+        .revert_immutable(&authority_keypair_1, &confirm_keypair.pubkey())
+        .await
+        .unwrap();
+
+    let program_data = simulator.get_program_data().await;
+    assert_eq!(
+        program_data.upgrade_authority_address,
+        Some(authority_keypair_1.pubkey())
+    );
+
+    simulator
+        .propose_immutable(&authority_keypair_1, &confirm_keypair.pubkey())
+        .await
+        .unwrap();
+
+    // The proposer can't confirm on its own
+    assert_eq!(
+        simulator
+            .confirm_immutable(&authority_keypair_1.pubkey(), &authority_keypair_1)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::ProgramNotEscrowed.into()
+    );
+
+    simulator
+        .confirm_immutable(&authority_keypair_1.pubkey(), &confirm_keypair)
+        .await
+        .unwrap();
+
+    let program_data = simulator.get_program_data().await;
+    assert_eq!(program_data.upgrade_authority_address, None);
+
+    // Once confirmed there is nothing left to revert
+    assert_eq!(
+        simulator
+            .revert_immutable(&authority_keypair_1, &confirm_keypair.pubkey())
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::ProgramImmutable.into()
+    );
+}
+
+#[tokio::test]
+async fn test_assert_authority() {
+    let (mut simulator, authority_keypair_1) = EscrowSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+
+    simulator
+        .assert_authority(Some(authority_keypair_1.pubkey()))
+        .await
+        .unwrap();
+    assert_eq!(
+        simulator
+            .assert_authority(Some(authority_keypair_2.pubkey()))
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::UnexpectedAuthority.into()
+    );
+    assert_eq!(
+        simulator.assert_authority(None).await.unwrap_err().unwrap(),
+        ErrorCode::UnexpectedAuthority.into()
+    );
+
+    simulator
+        .propose(&authority_keypair_1, &authority_keypair_2.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(
+        simulator
+            .assert_authority(Some(authority_keypair_1.pubkey()))
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::UnexpectedAuthority.into()
+    );
+    simulator
+        .assert_authority(Some(simulator.get_escrow_authority(
+            &authority_keypair_1.pubkey(),
+            &authority_keypair_2.pubkey(),
+        )))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_scoped() {
+    let (mut simulator, authority_keypair_1) = EscrowSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+
+    simulator
+        .propose_scoped(&authority_keypair_1, &authority_keypair_2.pubkey(), 1)
+        .await
+        .unwrap();
+
+    let program_data = simulator.get_program_data().await;
+    assert_eq!(
+        program_data.upgrade_authority_address,
+        Some(simulator.get_scoped_escrow_authority(
+            &authority_keypair_1.pubkey(),
+            &authority_keypair_2.pubkey(),
+            1
+        ))
+    );
+
+    // The shared escrow PDA and other nonces have no say over a scoped proposal
+    assert_eq!(
+        simulator
+            .accept(&authority_keypair_1.pubkey(), &authority_keypair_2)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::ProgramNotEscrowed.into()
+    );
+    assert_eq!(
+        simulator
+            .accept_scoped(&authority_keypair_1.pubkey(), &authority_keypair_2, 2)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::ProgramNotEscrowed.into()
+    );
+
+    simulator
+        .revert_scoped(&authority_keypair_1, &authority_keypair_2.pubkey(), 1)
+        .await
+        .unwrap();
+
+    let program_data = simulator.get_program_data().await;
+    assert_eq!(
+        program_data.upgrade_authority_address,
+        Some(authority_keypair_1.pubkey())
+    );
+
+    simulator
+        .propose_scoped(&authority_keypair_1, &authority_keypair_2.pubkey(), 2)
+        .await
+        .unwrap();
+    simulator
+        .accept_scoped(&authority_keypair_1.pubkey(), &authority_keypair_2, 2)
+        .await
+        .unwrap();
+
+    let program_data = simulator.get_program_data().await;
+    assert_eq!(
+        program_data.upgrade_authority_address,
+        Some(authority_keypair_2.pubkey())
+    );
+}
+
+#[tokio::test]
+async fn test_accept_and_propose() {
+    let (mut simulator, authority_keypair_1) = EscrowSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+    let authority_keypair_3 = Keypair::new();
+
+    simulator
+        .propose(&authority_keypair_1, &authority_keypair_2.pubkey())
+        .await
+        .unwrap();
+
+    // Only the new authority can move the program on
+    assert_eq!(
+        simulator
+            .accept_and_propose(
+                &authority_keypair_2.pubkey(),
+                &authority_keypair_1,
+                &authority_keypair_3.pubkey()
+            )
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::ProgramNotEscrowed.into()
+    );
+
+    simulator
+        .accept_and_propose(
+            &authority_keypair_1.pubkey(),
+            &authority_keypair_2,
+            &authority_keypair_3.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    let program_data = simulator.get_program_data().await;
+    assert_eq!(
+        program_data.upgrade_authority_address,
+        Some(
+            simulator
+                .get_escrow_authority(&authority_keypair_2.pubkey(), &authority_keypair_3.pubkey())
+        )
+    );
+
+    simulator
+        .accept(&authority_keypair_2.pubkey(), &authority_keypair_3)
+        .await
+        .unwrap();
+
+    let program_data = simulator.get_program_data().await;
+    assert_eq!(
+        program_data.upgrade_authority_address,
+        Some(authority_keypair_3.pubkey())
+    );
+}
+
+#[tokio::test]
+async fn test_accept_to_timelock() {
+    let (mut simulator, authority_keypair_1) = EscrowSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+    let final_authority = Keypair::new();
+
+    simulator
+        .propose(&authority_keypair_1, &authority_keypair_2.pubkey())
+        .await
+        .unwrap();
+
+    // Only the new authority can send the program to the timelock
+    assert_eq!(
+        simulator
+            .accept_to_timelock(
+                &authority_keypair_2.pubkey(),
+                &authority_keypair_1,
+                &final_authority.pubkey(),
+                0
+            )
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::ProgramNotEscrowed.into()
+    );
+
+    simulator
+        .accept_to_timelock(
+            &authority_keypair_1.pubkey(),
+            &authority_keypair_2,
+            &final_authority.pubkey(),
+            0,
+        )
+        .await
+        .unwrap();
+
+    let program_data = simulator.get_program_data().await;
+    assert_eq!(
+        program_data.upgrade_authority_address,
+        Some(simulator.get_timelock_authority(&final_authority.pubkey(), 0))
+    );
+}
+
+#[tokio::test]
+async fn test_errors() {
+    let (mut simulator, authority_keypair_1) = EscrowSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+    let authority_keypair_3 = Keypair::new();
+
+    assert_eq!(
+        simulator
+            .propose(&authority_keypair_2, &authority_keypair_3.pubkey())
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::NotCurrentAuthority.into()
+    );
+    assert_eq!(
+        simulator
+            .propose(&authority_keypair_1, &authority_keypair_1.pubkey())
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::SameAuthority.into()
+    );
+    assert_eq!(
+        simulator
+            .propose_immutable(&authority_keypair_1, &authority_keypair_1.pubkey())
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::SameAuthority.into()
+    );
+    assert_eq!(
+        simulator
+            .revert(&authority_keypair_1, &authority_keypair_2.pubkey())
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::ProgramNotEscrowed.into()
+    );
+    assert_eq!(
+        simulator
+            .accept(&authority_keypair_1.pubkey(), &authority_keypair_2)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::ProgramNotEscrowed.into()
+    );
+
+    simulator
+        .propose_immutable(&authority_keypair_1, &authority_keypair_2.pubkey())
+        .await
+        .unwrap();
+    simulator
+        .confirm_immutable(&authority_keypair_1.pubkey(), &authority_keypair_2)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        simulator
+            .propose(&authority_keypair_1, &authority_keypair_2.pubkey())
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::ProgramImmutable.into()
+    );
+    assert_eq!(
+        simulator
+            .accept(&authority_keypair_1.pubkey(), &authority_keypair_2)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::ProgramImmutable.into()
+    );
+}
+
+#[tokio::test]
+async fn test_new_authority_checks() {
+    let (mut simulator, authority_keypair_1) = EscrowSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+
+    assert_eq!(
+        simulator
+            .propose(&authority_keypair_1, &Pubkey::default())
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::NewAuthorityIsSystemProgram.into()
+    );
+    assert_eq!(
+        simulator
+            .propose(&authority_keypair_1, &crate::id())
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::NewAuthorityIsEscrowProgram.into()
+    );
+
+    // The confirm authority of an immutability proposal has to be able to sign too
+    assert_eq!(
+        simulator
+            .propose_immutable(&authority_keypair_1, &Pubkey::default())
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::NewAuthorityIsSystemProgram.into()
+    );
+    assert_eq!(
+        simulator
+            .propose_immutable(&authority_keypair_1, &crate::id())
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::NewAuthorityIsEscrowProgram.into()
+    );
+    assert_eq!(
+        simulator
+            .propose_immutable(&authority_keypair_1, &authority_keypair_1.pubkey())
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::SameAuthority.into()
+    );
+
+    // A PDA nobody owns is rejected in strict mode
+    let unowned_pda = simulator
+        .get_escrow_authority(&authority_keypair_1.pubkey(), &authority_keypair_2.pubkey());
+    assert_eq!(
+        simulator
+            .propose_strict(&authority_keypair_1, &unowned_pda)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::NewAuthorityNotAllowed.into()
+    );
+
+    simulator
+        .propose_strict(&authority_keypair_1, &authority_keypair_2.pubkey())
+        .await
+        .unwrap();
+
+    let program_data = simulator.get_program_data().await;
+    assert_eq!(
+        program_data.upgrade_authority_address,
+        Some(
+            simulator
+                .get_escrow_authority(&authority_keypair_1.pubkey(), &authority_keypair_2.pubkey())
+        )
+    );
+
+    // Chaining to the next escrow follows the same rules
+    assert_eq!(
+        simulator
+            .accept_and_propose(
+                &authority_keypair_1.pubkey(),
+                &authority_keypair_2,
+                &Pubkey::default()
+            )
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::NewAuthorityIsSystemProgram.into()
+    );
+    assert_eq!(
+        simulator
+            .accept_and_propose(
+                &authority_keypair_1.pubkey(),
+                &authority_keypair_2,
+                &crate::id()
+            )
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::NewAuthorityIsEscrowProgram.into()
+    );
+}
+
+#[tokio::test]
+async fn test_return_data() {
+    let (mut simulator, authority_keypair_1) = EscrowSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+
+    let (escrow_authority, bump) = Pubkey::find_program_address(
+        &[
+            authority_keypair_1.pubkey().as_ref(),
+            authority_keypair_2.pubkey().as_ref(),
+        ],
+        &crate::id(),
+    );
+    assert_eq!(
+        simulator
+            .simulate_propose(&authority_keypair_1, &authority_keypair_2.pubkey())
+            .await
+            .unwrap(),
+        EscrowState {
+            escrow_authority,
+            bump,
+            program_data: simulator.get_program_data_address(),
+        }
+    );
+
+    // Simulating doesn't move the authority
+    let program_data = simulator.get_program_data().await;
+    assert_eq!(
+        program_data.upgrade_authority_address,
+        Some(authority_keypair_1.pubkey())
+    );
+}
+
+#[tokio::test]
+async fn test_status() {
+    let (mut simulator, authority_keypair_1) = EscrowSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+    let final_authority = Keypair::new();
+    let candidates = vec![
+        authority_keypair_1.pubkey(),
+        authority_keypair_2.pubkey(),
+        final_authority.pubkey(),
+    ];
+
+    assert_eq!(
+        simulator
+            .status(candidates.clone(), vec![0], vec![])
+            .await
+            .unwrap(),
+        AuthorityStatus::Direct(authority_keypair_1.pubkey())
+    );
+
+    simulator
+        .propose(&authority_keypair_1, &authority_keypair_2.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(
+        simulator
+            .status(candidates.clone(), vec![0], vec![])
+            .await
+            .unwrap(),
+        AuthorityStatus::Escrowed {
+            current_authority: authority_keypair_1.pubkey(),
+            new_authority:     authority_keypair_2.pubkey(),
+        }
+    );
+
+    // Without the right candidates the escrow authority looks like any other key
+    assert_eq!(
+        simulator.status(vec![], vec![], vec![]).await.unwrap(),
+        AuthorityStatus::Direct(
+            simulator
+                .get_escrow_authority(&authority_keypair_1.pubkey(), &authority_keypair_2.pubkey())
+        )
+    );
+
+    simulator
+        .accept_to_timelock(
+            &authority_keypair_1.pubkey(),
+            &authority_keypair_2,
+            &final_authority.pubkey(),
+            0,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        simulator
+            .status(candidates.clone(), vec![0], vec![])
+            .await
+            .unwrap(),
+        AuthorityStatus::Timelocked {
+            new_authority: final_authority.pubkey(),
+            timestamp:     0,
+            matured:       true,
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_status_scoped_and_immutable() {
+    let (mut simulator, authority_keypair_1) = EscrowSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+    let candidates = vec![authority_keypair_1.pubkey(), authority_keypair_2.pubkey()];
+
+    simulator
+        .propose_scoped(&authority_keypair_1, &authority_keypair_2.pubkey(), 7)
+        .await
+        .unwrap();
+    assert_eq!(
+        simulator
+            .status(candidates.clone(), vec![], vec![0, 7])
+            .await
+            .unwrap(),
+        AuthorityStatus::ScopedEscrowed {
+            current_authority: authority_keypair_1.pubkey(),
+            new_authority:     authority_keypair_2.pubkey(),
+            nonce:             7,
+        }
+    );
+
+    simulator
+        .revert_scoped(&authority_keypair_1, &authority_keypair_2.pubkey(), 7)
+        .await
+        .unwrap();
+    simulator
+        .propose_immutable(&authority_keypair_1, &authority_keypair_2.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(
+        simulator
+            .status(candidates.clone(), vec![], vec![])
+            .await
+            .unwrap(),
+        AuthorityStatus::PendingImmutable {
+            current_authority: authority_keypair_1.pubkey(),
+            confirm_authority: authority_keypair_2.pubkey(),
+        }
+    );
+
+    // Every extra candidate adds a row of derivations, past the caps `status` refuses to run
+    assert_eq!(
+        simulator
+            .status(
+                vec![Pubkey::new_unique(); MAX_STATUS_CANDIDATES + 1],
+                vec![],
+                vec![]
+            )
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::TooManyStatusCandidates.into()
+    );
+    assert_eq!(
+        simulator
+            .status(candidates, vec![], vec![0; MAX_STATUS_NONCES + 1])
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::TooManyStatusCandidates.into()
+    );
+}
+
+#[tokio::test]
+async fn test_accept_checked() {
+    let (mut simulator, authority_keypair_1) = EscrowSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+
+    simulator
+        .propose(&authority_keypair_1, &authority_keypair_2.pubkey())
+        .await
+        .unwrap();
+
+    simulator
+        .accept_checked(&authority_keypair_1.pubkey(), &authority_keypair_2)
+        .await
+        .unwrap();
+
+    let program_data = simulator.get_program_data().await;
+    assert_eq!(
+        program_data.upgrade_authority_address,
+        Some(authority_keypair_2.pubkey())
+    );
+}
+
+#[tokio::test]
+async fn test_accept_checked_feature_inactive() {
+    let (mut simulator, authority_keypair_1) = EscrowSimulator::new_with_deactivated_features(&[
+        enable_bpf_loader_set_authority_checked_ix::id(),
+    ])
+    .await;
+    let authority_keypair_2 = Keypair::new();
+
+    simulator
+        .propose(&authority_keypair_1, &authority_keypair_2.pubkey())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        simulator
+            .accept_checked(&authority_keypair_1.pubkey(), &authority_keypair_2)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidInstructionData)
+    );
+
+    let program_data = simulator.get_program_data().await;
+    assert_eq!(
+        program_data.upgrade_authority_address,
+        Some(
+            simulator
+                .get_escrow_authority(&authority_keypair_1.pubkey(), &authority_keypair_2.pubkey())
+        )
+    );
+
+    // The unchecked instruction keeps working on older runtimes
+    simulator
+        .accept(&authority_keypair_1.pubkey(), &authority_keypair_2)
+        .await
+        .unwrap();
+
+    let program_data = simulator.get_program_data().await;
+    assert_eq!(
+        program_data.upgrade_authority_address,
+        Some(authority_keypair_2.pubkey())
+    );
+}
+
+#[tokio::test]
+async fn test_memo() {
+    let (mut simulator, authority_keypair_1) = EscrowSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+
+    assert_eq!(
+        simulator
+            .propose_with_memo(
+                &authority_keypair_1,
+                &authority_keypair_2.pubkey(),
+                Some(HandoverMemo::Reason(String::new()))
+            )
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::EmptyReason.into()
+    );
+    assert_eq!(
+        simulator
+            .propose_with_memo(
+                &authority_keypair_1,
+                &authority_keypair_2.pubkey(),
+                Some(HandoverMemo::Reason("a".repeat(MAX_REASON_LEN + 1)))
+            )
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::ReasonTooLong.into()
+    );
+
+    simulator
+        .propose_with_memo(
+            &authority_keypair_1,
+            &authority_keypair_2.pubkey(),
+            Some(HandoverMemo::Reason("a".repeat(MAX_REASON_LEN))),
+        )
+        .await
+        .unwrap();
+    simulator
+        .revert_with_memo(
+            &authority_keypair_1,
+            &authority_keypair_2.pubkey(),
+            Some(HandoverMemo::Reason("Wrong multisig".to_string())),
+        )
+        .await
+        .unwrap();
+
+    let program_data = simulator.get_program_data().await;
+    assert_eq!(
+        program_data.upgrade_authority_address,
+        Some(authority_keypair_1.pubkey())
+    );
+
+    simulator
+        .propose(&authority_keypair_1, &authority_keypair_2.pubkey())
+        .await
+        .unwrap();
+    simulator
+        .accept_with_memo(
+            &authority_keypair_1.pubkey(),
+            &authority_keypair_2,
+            Some(HandoverMemo::Hash([7; 32])),
+        )
+        .await
+        .unwrap();
+
+    let program_data = simulator.get_program_data().await;
+    assert_eq!(
+        program_data.upgrade_authority_address,
+        Some(authority_keypair_2.pubkey())
+    );
+
+    // Chained handovers check their memo the same way
+    let authority_keypair_3 = Keypair::new();
+    let authority_keypair_4 = Keypair::new();
+    let final_authority = Keypair::new();
+    simulator
+        .propose(&authority_keypair_2, &authority_keypair_3.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(
+        simulator
+            .accept_and_propose_with_memo(
+                &authority_keypair_2.pubkey(),
+                &authority_keypair_3,
+                &authority_keypair_4.pubkey(),
+                Some(HandoverMemo::Reason(String::new()))
+            )
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::EmptyReason.into()
+    );
+    simulator
+        .accept_and_propose_with_memo(
+            &authority_keypair_2.pubkey(),
+            &authority_keypair_3,
+            &authority_keypair_4.pubkey(),
+            Some(HandoverMemo::Reason(
+                "Straight to the next team".to_string(),
+            )),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        simulator
+            .accept_to_timelock_with_memo(
+                &authority_keypair_3.pubkey(),
+                &authority_keypair_4,
+                &final_authority.pubkey(),
+                0,
+                Some(HandoverMemo::Reason("a".repeat(MAX_REASON_LEN + 1)))
+            )
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::ReasonTooLong.into()
+    );
+    simulator
+        .accept_to_timelock_with_memo(
+            &authority_keypair_3.pubkey(),
+            &authority_keypair_4,
+            &final_authority.pubkey(),
+            0,
+            Some(HandoverMemo::Hash([7; 32])),
+        )
+        .await
+        .unwrap();
+
+    let program_data = simulator.get_program_data().await;
+    assert_eq!(
+        program_data.upgrade_authority_address,
+        Some(simulator.get_timelock_authority(&final_authority.pubkey(), 0))
+    );
+}
+
+#[tokio::test]
+async fn test_scoped_memo() {
+    let (mut simulator, authority_keypair_1) = EscrowSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+
+    assert_eq!(
+        simulator
+            .propose_scoped_with_memo(
+                &authority_keypair_1,
+                &authority_keypair_2.pubkey(),
+                3,
+                Some(HandoverMemo::Reason(String::new()))
+            )
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::EmptyReason.into()
+    );
+    simulator
+        .propose_scoped_with_memo(
+            &authority_keypair_1,
+            &authority_keypair_2.pubkey(),
+            3,
+            Some(HandoverMemo::Reason(
+                "Hand over this program only".to_string(),
+            )),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        simulator
+            .revert_scoped_with_memo(
+                &authority_keypair_1,
+                &authority_keypair_2.pubkey(),
+                3,
+                Some(HandoverMemo::Reason("a".repeat(MAX_REASON_LEN + 1)))
+            )
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::ReasonTooLong.into()
+    );
+    simulator
+        .revert_scoped_with_memo(
+            &authority_keypair_1,
+            &authority_keypair_2.pubkey(),
+            3,
+            Some(HandoverMemo::Hash([3; 32])),
+        )
+        .await
+        .unwrap();
+
+    simulator
+        .propose_scoped(&authority_keypair_1, &authority_keypair_2.pubkey(), 3)
+        .await
+        .unwrap();
+    assert_eq!(
+        simulator
+            .accept_scoped_with_memo(
+                &authority_keypair_1.pubkey(),
+                &authority_keypair_2,
+                3,
+                Some(HandoverMemo::Reason(String::new()))
+            )
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::EmptyReason.into()
+    );
+    simulator
+        .accept_scoped_with_memo(
+            &authority_keypair_1.pubkey(),
+            &authority_keypair_2,
+            3,
+            Some(HandoverMemo::Hash([4; 32])),
+        )
+        .await
+        .unwrap();
+
+    let program_data = simulator.get_program_data().await;
+    assert_eq!(
+        program_data.upgrade_authority_address,
+        Some(authority_keypair_2.pubkey())
+    );
+}
+
+#[tokio::test]
+async fn test_proposal_record() {
+    let (mut simulator, authority_keypair_1) = EscrowSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+    let payer_keypair = simulator.create_payer().await;
+
+    // The current authority holds no SOL, the payer covers the rent
+    simulator
+        .propose_with_record(
+            &authority_keypair_1,
+            &authority_keypair_2.pubkey(),
+            &payer_keypair,
+            Some(HandoverMemo::Reason("Rotate to multisig".to_string())),
+        )
+        .await
+        .unwrap();
+
+    let record = simulator
+        .get_proposal_record(&authority_keypair_1.pubkey(), &authority_keypair_2.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(record.current_authority, authority_keypair_1.pubkey());
+    assert_eq!(record.new_authority, authority_keypair_2.pubkey());
+    assert_eq!(record.rent_payer, payer_keypair.pubkey());
+    assert_eq!(
+        record.memo,
+        Some(HandoverMemo::Reason("Rotate to multisig".to_string()))
+    );
+    assert_eq!(
+        simulator.get_balance(&payer_keypair.pubkey()).await,
+        LAMPORTS_PER_SOL - Rent::default().minimum_balance(ProposalRecord::LEN)
+    );
+
+    let program_data = simulator.get_program_data().await;
+    assert_eq!(
+        program_data.upgrade_authority_address,
+        Some(
+            simulator
+                .get_escrow_authority(&authority_keypair_1.pubkey(), &authority_keypair_2.pubkey())
+        )
+    );
+
+    assert_eq!(
+        simulator
+            .close_proposal_record(
+                &authority_keypair_1.pubkey(),
+                &authority_keypair_2.pubkey(),
+                &payer_keypair.pubkey()
+            )
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::ProposalStillPending.into()
+    );
+
+    simulator
+        .accept(&authority_keypair_1.pubkey(), &authority_keypair_2)
+        .await
+        .unwrap();
+    simulator
+        .close_proposal_record(
+            &authority_keypair_1.pubkey(),
+            &authority_keypair_2.pubkey(),
+            &payer_keypair.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    assert!(simulator
+        .get_proposal_record(&authority_keypair_1.pubkey(), &authority_keypair_2.pubkey())
+        .await
+        .is_none());
+    assert_eq!(
+        simulator.get_balance(&payer_keypair.pubkey()).await,
+        LAMPORTS_PER_SOL
+    );
+}
+
+#[tokio::test]
+async fn test_status_compute_budget() {
+    let (mut simulator, authority_keypair) = EscrowSimulator::new().await;
+
+    // Nothing matches, so `status` derives every address its caps allow
+    let candidates = (0..MAX_STATUS_CANDIDATES)
+        .map(|_| Pubkey::new_unique())
+        .collect();
+    let timestamps = (0..MAX_STATUS_TIMESTAMPS as i64).collect();
+    let nonces = (0..MAX_STATUS_NONCES as u64).collect();
+    let (status, units_consumed) = simulator
+        .status_with_units(candidates, timestamps, nonces)
+        .await
+        .unwrap();
+    assert_eq!(status, AuthorityStatus::Direct(authority_keypair.pubkey()));
+    assert!(units_consumed < u64::from(STATUS_COMPUTE_UNIT_LIMIT));
+}
+
+#[test]
+fn test_common_program_ids() {
+    // `status` derives escrow addresses from the id in the common crate
+    assert_eq!(crate::ID, program_authority_common::escrow_program::ID);
+    assert_eq!(
+        program_authority_timelock::ID,
+        program_authority_common::timelock_program::ID
+    );
+}
@@ -1,15 +1,33 @@
 #![deny(warnings)]
 #![allow(clippy::result_large_err)]
 
-use anchor_lang::{
-    prelude::*,
-    solana_program::{
-        bpf_loader_upgradeable,
-        program::{
-            invoke,
-            invoke_signed,
+pub use program_authority_common::{
+    find_authority_status,
+    AuthorityStatus,
+    HandoverMemo,
+    MAX_REASON_LEN,
+    MAX_STATUS_CANDIDATES,
+    MAX_STATUS_NONCES,
+    MAX_STATUS_TIMESTAMPS,
+    STATUS_COMPUTE_UNIT_LIMIT,
+};
+use {
+    anchor_lang::{
+        prelude::*,
+        solana_program::{
+            bpf_loader_upgradeable,
+            program::{
+                invoke,
+                invoke_signed,
+            },
         },
     },
+    program_authority_common::{
+        find_unusable_authority,
+        is_strict_authority,
+        InvalidMemo,
+        UnusableAuthority,
+    },
 };
 
 #[cfg(test)]
@@ -17,16 +35,46 @@ mod tests;
 
 declare_id!("t1monUESMN3oVEoAw9HQkaVX6hUGg3hkhN5wKaTvV5f");
 const ONE_YEAR: i64 = 365 * 24 * 60 * 60;
+pub const COMMITMENT_RECORD_SEED: &[u8] = b"commitment";
 
 #[program]
 pub mod program_authority_timelock {
     use super::*;
 
-    pub fn commit(ctx: Context<Commit>, timestamp: i64) -> Result<()> {
+    pub fn commit(ctx: Context<Commit>, timestamp: i64) -> Result<TimelockState> {
+        commit_handover(ctx, timestamp, None)
+    }
+
+    pub fn commit_with_memo(
+        ctx: Context<Commit>,
+        timestamp: i64,
+        memo: HandoverMemo,
+    ) -> Result<TimelockState> {
+        commit_handover(ctx, timestamp, Some(memo))
+    }
+
+    pub fn commit_strict(
+        ctx: Context<Commit>,
+        timestamp: i64,
+        memo: Option<HandoverMemo>,
+    ) -> Result<TimelockState> {
+        check_strict_new_authority(&ctx.accounts.new_authority)?;
+        commit_handover(ctx, timestamp, memo)
+    }
+
+    pub fn commit_with_record(
+        ctx: Context<CommitWithRecord>,
+        timestamp: i64,
+        memo: Option<HandoverMemo>,
+    ) -> Result<TimelockState> {
         let current_authority = &ctx.accounts.current_authority;
+        let new_authority = &ctx.accounts.new_authority;
         let escrow_authority = &ctx.accounts.escrow_authority;
         let program_account = &ctx.accounts.program_account;
 
+        check_new_authority(&current_authority.key(), &new_authority.key())?;
+        log_memo(&memo)?;
+
         invoke(
             &bpf_loader_upgradeable::set_upgrade_authority(
                 &program_account.key(),
@@ -37,23 +85,75 @@ pub mod program_authority_timelock {
         )?;
 
         // Check that the timelock is no longer than 1 year
-        if Clock::get()?.unix_timestamp.saturating_add(ONE_YEAR) < timestamp {
+        let now = Clock::get()?.unix_timestamp;
+        if now.saturating_add(ONE_YEAR) < timestamp {
             return Err(ErrorCode::TimestampTooLate.into());
         }
 
+        emit_handover(
+            program_account.key(),
+            current_authority.key(),
+            escrow_authority.key(),
+            memo.clone(),
+        );
+
+        ctx.accounts.commitment_record.set_inner(CommitmentRecord {
+            current_authority: current_authority.key(),
+            new_authority: new_authority.key(),
+            program_account: program_account.key(),
+            rent_payer: ctx.accounts.payer.key(),
+            unlock_timestamp: timestamp,
+            created_at: now,
+            memo,
+        });
+
+        Ok(TimelockState {
+            escrow_authority: escrow_authority.key(),
+            bump:             *ctx.bumps.get("escrow_authority").unwrap(),
+            program_data:     ctx.accounts.program_data.key(),
+            unlock_timestamp: timestamp,
+        })
+    }
+
+    pub fn close_commitment_record(ctx: Context<CloseCommitmentRecord>) -> Result<()> {
+        // Anyone can close the record once the program was transferred out,
+        // the rent always goes back to the recorded payer
+        if ctx.accounts.program_data.upgrade_authority_address
+            == Some(ctx.accounts.escrow_authority.key())
+        {
+            return Err(ErrorCode::CommitmentStillPending.into());
+        }
         Ok(())
     }
 
     pub fn transfer(ctx: Context<Transfer>, timestamp: i64) -> Result<()> {
+        transfer_handover(ctx, timestamp, None)
+    }
+
+    pub fn transfer_with_memo(
+        ctx: Context<Transfer>,
+        timestamp: i64,
+        memo: HandoverMemo,
+    ) -> Result<()> {
+        transfer_handover(ctx, timestamp, Some(memo))
+    }
+
+    pub fn transfer_checked(
+        ctx: Context<TransferChecked>,
+        timestamp: i64,
+        memo: Option<HandoverMemo>,
+    ) -> Result<()> {
         let new_authority = &ctx.accounts.new_authority;
         let escrow_authority = &ctx.accounts.escrow_authority;
         let program_account = &ctx.accounts.program_account;
 
+        log_memo(&memo)?;
+
         invoke_signed(
-            &bpf_loader_upgradeable::set_upgrade_authority(
+            &bpf_loader_upgradeable::set_upgrade_authority_checked(
                 &program_account.key(),
                 &escrow_authority.key(),
-                Some(&new_authority.key()),
+                &new_authority.key(),
             ),
             &ctx.accounts.to_account_infos(),
             &[&[
@@ -67,7 +167,221 @@ pub mod program_authority_timelock {
             return Err(ErrorCode::TimestampTooEarly.into());
         }
 
+        emit_handover(
+            program_account.key(),
+            escrow_authority.key(),
+            new_authority.key(),
+            memo,
+        );
+
+        Ok(())
+    }
+
+    pub fn status(
+        ctx: Context<Status>,
+        candidates: Vec<Pubkey>,
+        timestamps: Vec<i64>,
+        nonces: Vec<u64>,
+    ) -> Result<AuthorityStatus> {
+        if candidates.len() > MAX_STATUS_CANDIDATES
+            || timestamps.len() > MAX_STATUS_TIMESTAMPS
+            || nonces.len() > MAX_STATUS_NONCES
+        {
+            return Err(ErrorCode::TooManyStatusCandidates.into());
+        }
+
+        Ok(find_authority_status(
+            &ctx.accounts.program_account.key(),
+            ctx.accounts.program_data.upgrade_authority_address,
+            &candidates,
+            &timestamps,
+            &nonces,
+            Clock::get()?.unix_timestamp,
+        ))
+    }
+
+    pub fn extend_program(
+        ctx: Context<ExtendProgram>,
+        timestamp: i64,
+        additional_bytes: u32,
+    ) -> Result<()> {
+        let payer = &ctx.accounts.payer;
+        let program_account = &ctx.accounts.program_account;
+
+        // The timestamp only goes into the seeds of the escrow authority, logged to find it back
+        msg!("Extending a program timelocked until {}", timestamp);
+
+        invoke(
+            &bpf_loader_upgradeable::extend_program(
+                &program_account.key(),
+                Some(&payer.key()),
+                additional_bytes,
+            ),
+            &ctx.accounts.to_account_infos(),
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Returned by `commit` so callers don't have to derive the escrow authority themselves
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TimelockState {
+    pub escrow_authority: Pubkey,
+    pub bump:             u8,
+    pub program_data:     Pubkey,
+    pub unlock_timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityHandover {
+    pub program_account: Pubkey,
+    pub from_authority:  Pubkey,
+    pub to_authority:    Pubkey,
+    pub memo:            Option<HandoverMemo>,
+}
+
+/// Created by `commit_with_record` and paid for by a separate payer, who gets the rent back on close
+#[account]
+pub struct CommitmentRecord {
+    pub current_authority: Pubkey,
+    pub new_authority:     Pubkey,
+    pub program_account:   Pubkey,
+    pub rent_payer:        Pubkey,
+    pub unlock_timestamp:  i64,
+    pub created_at:        i64,
+    pub memo:              Option<HandoverMemo>,
+}
+
+impl CommitmentRecord {
+    // The longest memo is a reason of `MAX_REASON_LEN` bytes
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 32 + 8 + 8 + 1 + 1 + 4 + MAX_REASON_LEN;
+}
+
+/// Shared by `commit`, `commit_with_memo` and `commit_strict`
+fn commit_handover(
+    ctx: Context<Commit>,
+    timestamp: i64,
+    memo: Option<HandoverMemo>,
+) -> Result<TimelockState> {
+    let current_authority = &ctx.accounts.current_authority;
+    let escrow_authority = &ctx.accounts.escrow_authority;
+    let program_account = &ctx.accounts.program_account;
+
+    check_new_authority(&current_authority.key(), &ctx.accounts.new_authority.key())?;
+    log_memo(&memo)?;
+
+    invoke(
+        &bpf_loader_upgradeable::set_upgrade_authority(
+            &program_account.key(),
+            &current_authority.key(),
+            Some(&escrow_authority.key()),
+        ),
+        &ctx.accounts.to_account_infos(),
+    )?;
+
+    // Check that the timelock is no longer than 1 year
+    if Clock::get()?.unix_timestamp.saturating_add(ONE_YEAR) < timestamp {
+        return Err(ErrorCode::TimestampTooLate.into());
+    }
+
+    emit_handover(
+        program_account.key(),
+        current_authority.key(),
+        escrow_authority.key(),
+        memo,
+    );
+
+    Ok(TimelockState {
+        escrow_authority: escrow_authority.key(),
+        bump:             *ctx.bumps.get("escrow_authority").unwrap(),
+        program_data:     ctx.accounts.program_data.key(),
+        unlock_timestamp: timestamp,
+    })
+}
+
+/// Shared by `transfer` and `transfer_with_memo`
+fn transfer_handover(
+    ctx: Context<Transfer>,
+    timestamp: i64,
+    memo: Option<HandoverMemo>,
+) -> Result<()> {
+    let new_authority = &ctx.accounts.new_authority;
+    let escrow_authority = &ctx.accounts.escrow_authority;
+    let program_account = &ctx.accounts.program_account;
+
+    log_memo(&memo)?;
+
+    invoke_signed(
+        &bpf_loader_upgradeable::set_upgrade_authority(
+            &program_account.key(),
+            &escrow_authority.key(),
+            Some(&new_authority.key()),
+        ),
+        &ctx.accounts.to_account_infos(),
+        &[&[
+            new_authority.key().as_ref(),
+            timestamp.to_be_bytes().as_ref(),
+            &[*ctx.bumps.get("escrow_authority").unwrap()],
+        ]],
+    )?;
+
+    if Clock::get()?.unix_timestamp < timestamp {
+        return Err(ErrorCode::TimestampTooEarly.into());
+    }
+
+    emit_handover(
+        program_account.key(),
+        escrow_authority.key(),
+        new_authority.key(),
+        memo,
+    );
+
+    Ok(())
+}
+
+/// Emits the `AuthorityHandover` event of every handover
+fn emit_handover(
+    program_account: Pubkey,
+    from_authority: Pubkey,
+    to_authority: Pubkey,
+    memo: Option<HandoverMemo>,
+) {
+    emit!(AuthorityHandover {
+        program_account,
+        from_authority,
+        to_authority,
+        memo,
+    });
+}
+
+/// Rejects empty or oversized reasons and writes the memo to the program logs
+fn log_memo(memo: &Option<HandoverMemo>) -> Result<()> {
+    match program_authority_common::log_memo(memo) {
+        Err(InvalidMemo::EmptyReason) => Err(ErrorCode::EmptyReason.into()),
+        Err(InvalidMemo::ReasonTooLong) => Err(ErrorCode::ReasonTooLong.into()),
+        Ok(()) => Ok(()),
+    }
+}
+
+fn check_new_authority(current_authority: &Pubkey, new_authority: &Pubkey) -> Result<()> {
+    match find_unusable_authority(current_authority, new_authority, &crate::ID) {
+        Some(UnusableAuthority::SystemProgram) => {
+            Err(ErrorCode::NewAuthorityIsSystemProgram.into())
+        }
+        Some(UnusableAuthority::HandoverProgram) => {
+            Err(ErrorCode::NewAuthorityIsTimelockProgram.into())
+        }
+        Some(UnusableAuthority::SameAuthority) => Err(ErrorCode::SameAuthority.into()),
+        None => Ok(()),
+    }
+}
+
+fn check_strict_new_authority(new_authority: &AccountInfo) -> Result<()> {
+    if is_strict_authority(new_authority) {
         Ok(())
+    } else {
+        Err(ErrorCode::NewAuthorityNotAllowed.into())
     }
 }
 
@@ -86,6 +400,41 @@ pub struct Commit<'info> {
     pub bpf_upgradable_loader: Program<'info, BpfUpgradableLoader>,
 }
 
+#[derive(Accounts)]
+#[instruction(timestamp : i64)]
+pub struct CommitWithRecord<'info> {
+    pub current_authority:     Signer<'info>,
+    /// CHECK: Unchecked new authority, can be a native wallet or a PDA of another program
+    pub new_authority:         AccountInfo<'info>,
+    #[account(seeds = [new_authority.key().as_ref(), timestamp.to_be_bytes().as_ref()], bump)]
+    pub escrow_authority:      SystemAccount<'info>,
+    #[account(init, payer = payer, space = CommitmentRecord::LEN, seeds = [COMMITMENT_RECORD_SEED, escrow_authority.key().as_ref(), program_account.key().as_ref()], bump)]
+    pub commitment_record:     Account<'info, CommitmentRecord>,
+    #[account(mut)]
+    pub payer:                 Signer<'info>,
+    #[account(executable, constraint = matches!(program_account.as_ref(), UpgradeableLoaderState::Program{..}))]
+    pub program_account:       Account<'info, UpgradeableLoaderState>,
+    #[account(mut, seeds = [program_account.key().as_ref()], bump, seeds::program = bpf_upgradable_loader.key())]
+    pub program_data:          Account<'info, ProgramData>,
+    pub system_program:        Program<'info, System>,
+    pub bpf_upgradable_loader: Program<'info, BpfUpgradableLoader>,
+}
+
+#[derive(Accounts)]
+pub struct CloseCommitmentRecord<'info> {
+    #[account(mut, close = rent_payer, has_one = rent_payer, has_one = program_account, seeds = [COMMITMENT_RECORD_SEED, escrow_authority.key().as_ref(), program_account.key().as_ref()], bump)]
+    pub commitment_record:     Account<'info, CommitmentRecord>,
+    /// CHECK: Has to be the payer recorded in the commitment record
+    #[account(mut)]
+    pub rent_payer:            AccountInfo<'info>,
+    #[account(seeds = [commitment_record.new_authority.as_ref(), commitment_record.unlock_timestamp.to_be_bytes().as_ref()], bump)]
+    pub escrow_authority:      SystemAccount<'info>,
+    pub program_account:       Account<'info, UpgradeableLoaderState>,
+    #[account(seeds = [program_account.key().as_ref()], bump, seeds::program = bpf_upgradable_loader.key())]
+    pub program_data:          Account<'info, ProgramData>,
+    pub bpf_upgradable_loader: Program<'info, BpfUpgradableLoader>,
+}
+
 #[derive(Accounts)]
 #[instruction(timestamp : i64)]
 pub struct Transfer<'info> {
@@ -100,6 +449,44 @@ pub struct Transfer<'info> {
     pub bpf_upgradable_loader: Program<'info, BpfUpgradableLoader>,
 }
 
+#[derive(Accounts)]
+#[instruction(timestamp : i64)]
+pub struct TransferChecked<'info> {
+    pub new_authority:         Signer<'info>,
+    #[account(seeds = [new_authority.key().as_ref(), timestamp.to_be_bytes().as_ref()], bump)]
+    pub escrow_authority:      SystemAccount<'info>,
+    #[account(executable, constraint = matches!(program_account.as_ref(), UpgradeableLoaderState::Program{..}))]
+    pub program_account:       Account<'info, UpgradeableLoaderState>,
+    #[account(mut, seeds = [program_account.key().as_ref()], bump, seeds::program = bpf_upgradable_loader.key())]
+    pub program_data:          Account<'info, ProgramData>,
+    pub bpf_upgradable_loader: Program<'info, BpfUpgradableLoader>,
+}
+
+#[derive(Accounts)]
+pub struct Status<'info> {
+    #[account(executable, constraint = matches!(program_account.as_ref(), UpgradeableLoaderState::Program{..}))]
+    pub program_account:       Account<'info, UpgradeableLoaderState>,
+    #[account(seeds = [program_account.key().as_ref()], bump, seeds::program = bpf_upgradable_loader.key())]
+    pub program_data:          Account<'info, ProgramData>,
+    pub bpf_upgradable_loader: Program<'info, BpfUpgradableLoader>,
+}
+
+#[derive(Accounts)]
+#[instruction(timestamp : i64)]
+pub struct ExtendProgram<'info> {
+    pub new_authority:         Signer<'info>,
+    #[account(seeds = [new_authority.key().as_ref(), timestamp.to_be_bytes().as_ref()], bump)]
+    pub escrow_authority:      SystemAccount<'info>,
+    #[account(mut)]
+    pub payer:                 Signer<'info>,
+    #[account(mut, executable, constraint = matches!(program_account.as_ref(), UpgradeableLoaderState::Program{..}))]
+    pub program_account:       Account<'info, UpgradeableLoaderState>,
+    #[account(mut, seeds = [program_account.key().as_ref()], bump, seeds::program = bpf_upgradable_loader.key(), constraint = program_data.upgrade_authority_address == Some(escrow_authority.key()) @ ErrorCode::ProgramNotTimelocked)]
+    pub program_data:          Account<'info, ProgramData>,
+    pub system_program:        Program<'info, System>,
+    pub bpf_upgradable_loader: Program<'info, BpfUpgradableLoader>,
+}
+
 #[derive(Clone)]
 pub struct BpfUpgradableLoader {}
 
@@ -116,4 +503,22 @@ pub enum ErrorCode {
     TimestampTooEarly,
     #[msg("Timestamp too late")]
     TimestampTooLate,
+    #[msg("New authority is the same as the current authority")]
+    SameAuthority,
+    #[msg("New authority can't be the system program")]
+    NewAuthorityIsSystemProgram,
+    #[msg("New authority can't be the timelock program")]
+    NewAuthorityIsTimelockProgram,
+    #[msg("New authority is neither on curve nor owned by an allowlisted program")]
+    NewAuthorityNotAllowed,
+    #[msg("Reason can't be empty")]
+    EmptyReason,
+    #[msg("Reason is longer than 200 bytes")]
+    ReasonTooLong,
+    #[msg("Program is still held by the timelock authority")]
+    CommitmentStillPending,
+    #[msg("Too many candidates, timestamps or nonces to check")]
+    TooManyStatusCandidates,
+    #[msg("Program is not held by the timelock authority")]
+    ProgramNotTimelocked,
 }
@@ -1,5 +1,12 @@
 use {
-    crate::instruction,
+    crate::{
+        AuthorityStatus,
+        CommitmentRecord,
+        HandoverMemo,
+        TimelockState,
+        COMMITMENT_RECORD_SEED,
+        STATUS_COMPUTE_UNIT_LIMIT,
+    },
     anchor_lang::{
         prelude::{
             Clock,
@@ -8,9 +15,8 @@ use {
             UpgradeableLoaderState,
         },
         AccountDeserialize,
-        InstructionData,
+        AnchorDeserialize,
         ProgramData,
-        ToAccountMetas,
     },
     solana_program_test::{
         read_file,
@@ -22,13 +28,18 @@ use {
     solana_sdk::{
         account::Account,
         bpf_loader_upgradeable,
+        compute_budget::ComputeBudgetInstruction,
         instruction::Instruction,
+        native_token::LAMPORTS_PER_SOL,
+        program::MAX_RETURN_DATA,
         signature::{
             Keypair,
             Signer,
         },
         stake_history::Epoch,
+        system_instruction,
         transaction::Transaction,
+        transaction_context::TransactionReturnData,
     },
     std::path::PathBuf,
 };
@@ -42,12 +53,22 @@ pub struct TimelockSimulator {
 
 impl TimelockSimulator {
     pub async fn new() -> (TimelockSimulator, Keypair) {
+        Self::new_with_deactivated_features(&[]).await
+    }
+
+    /// Same as `new` but on a runtime where the given features are not active
+    pub async fn new_with_deactivated_features(
+        features: &[Pubkey],
+    ) -> (TimelockSimulator, Keypair) {
         let mut bpf_data = read_file(PathBuf::from("../../tests/fixtures/helloworld.so"));
 
         let timelock_address = crate::id();
 
         let mut program_test =
             ProgramTest::new("program_authority_timelock", timelock_address, None);
+        for feature in features {
+            program_test.deactivate_feature(*feature);
+        }
         let upgrade_authority = Keypair::new();
 
         let helloworld_address = add_program_as_upgradable(
@@ -140,51 +161,306 @@ impl TimelockSimulator {
             .await
     }
 
+    async fn simulate_ix(
+        &mut self,
+        instruction: Instruction,
+        signers: &Vec<&Keypair>,
+    ) -> Result<Option<TransactionReturnData>, BanksClientError> {
+        Ok(self.simulate_ixs(&[instruction], signers).await?.0)
+    }
+
+    /// Simulates the instructions in one transaction, returns the return data and the compute
+    /// units consumed
+    async fn simulate_ixs(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &Vec<&Keypair>,
+    ) -> Result<(Option<TransactionReturnData>, u64), BanksClientError> {
+        let mut transaction =
+            Transaction::new_with_payer(instructions, Some(&self.context.payer.pubkey()));
+
+        let blockhash = self
+            .context
+            .banks_client
+            .get_latest_blockhash()
+            .await
+            .unwrap();
+
+        transaction.partial_sign(&[&self.context.payer], blockhash);
+        transaction.partial_sign(signers, blockhash);
+        let simulation = self
+            .context
+            .banks_client
+            .simulate_transaction(transaction)
+            .await?;
+        simulation
+            .result
+            .unwrap()
+            .map_err(BanksClientError::TransactionError)?;
+        let simulation_details = simulation.simulation_details.unwrap();
+        Ok((
+            simulation_details.return_data,
+            simulation_details.units_consumed,
+        ))
+    }
+
     pub async fn commit(
         &mut self,
         current_authority_keypair: &Keypair,
         new_authority: &Pubkey,
         timestamp: i64,
     ) -> Result<(), BanksClientError> {
-        let account_metas = crate::accounts::Commit::create(
+        self.commit_with_memo(current_authority_keypair, new_authority, timestamp, None)
+            .await
+    }
+
+    pub async fn commit_with_memo(
+        &mut self,
+        current_authority_keypair: &Keypair,
+        new_authority: &Pubkey,
+        timestamp: i64,
+        memo: Option<HandoverMemo>,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::commit_ix(
             &current_authority_keypair.pubkey(),
             new_authority,
             &self.helloworld_address,
-            &self.timelock_address,
             timestamp,
-        )
-        .to_account_metas(None);
+            memo,
+        );
 
-        let instruction = Instruction {
-            program_id: self.timelock_address,
-            accounts:   account_metas,
-            data:       instruction::Commit { timestamp }.data(),
-        };
+        self.process_ix(instruction, &vec![current_authority_keypair])
+            .await
+    }
+
+    /// Simulates `commit` and decodes its return data without changing the bank
+    pub async fn simulate_commit(
+        &mut self,
+        current_authority_keypair: &Keypair,
+        new_authority: &Pubkey,
+        timestamp: i64,
+    ) -> Result<TimelockState, BanksClientError> {
+        let instruction = program_authority_client::commit_ix(
+            &current_authority_keypair.pubkey(),
+            new_authority,
+            &self.helloworld_address,
+            timestamp,
+            None,
+        );
+
+        let return_data = self
+            .simulate_ix(instruction, &vec![current_authority_keypair])
+            .await?
+            .unwrap();
+        assert_eq!(return_data.program_id, self.timelock_address);
+        Ok(TimelockState::try_from_slice(&return_data.data).unwrap())
+    }
+
+    pub async fn commit_strict(
+        &mut self,
+        current_authority_keypair: &Keypair,
+        new_authority: &Pubkey,
+        timestamp: i64,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::commit_strict_ix(
+            &current_authority_keypair.pubkey(),
+            new_authority,
+            &self.helloworld_address,
+            timestamp,
+            None,
+        );
 
         self.process_ix(instruction, &vec![current_authority_keypair])
             .await
     }
 
+    /// Creates a keypair funded by `context.payer`, to pay rent separately from the fee payer
+    pub async fn create_payer(&mut self) -> Keypair {
+        let payer = Keypair::new();
+        let instruction = system_instruction::transfer(
+            &self.context.payer.pubkey(),
+            &payer.pubkey(),
+            LAMPORTS_PER_SOL,
+        );
+        self.process_ix(instruction, &vec![]).await.unwrap();
+        payer
+    }
+
+    pub async fn commit_with_record(
+        &mut self,
+        current_authority_keypair: &Keypair,
+        new_authority: &Pubkey,
+        timestamp: i64,
+        payer_keypair: &Keypair,
+        memo: Option<HandoverMemo>,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::commit_with_record_ix(
+            &current_authority_keypair.pubkey(),
+            new_authority,
+            &payer_keypair.pubkey(),
+            &self.helloworld_address,
+            timestamp,
+            memo,
+        );
+
+        self.process_ix(instruction, &vec![current_authority_keypair, payer_keypair])
+            .await
+    }
+
+    pub async fn close_commitment_record(
+        &mut self,
+        new_authority: &Pubkey,
+        timestamp: i64,
+        rent_payer: &Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::close_commitment_record_ix(
+            new_authority,
+            rent_payer,
+            &self.helloworld_address,
+            timestamp,
+        );
+
+        self.process_ix(instruction, &vec![]).await
+    }
+
     pub async fn transfer(
         &mut self,
         new_authority: &Pubkey,
         timestamp: i64,
     ) -> Result<(), BanksClientError> {
-        let account_metas = crate::accounts::Transfer::create(
+        self.transfer_with_memo(new_authority, timestamp, None)
+            .await
+    }
+
+    pub async fn transfer_with_memo(
+        &mut self,
+        new_authority: &Pubkey,
+        timestamp: i64,
+        memo: Option<HandoverMemo>,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::transfer_ix(
             new_authority,
             &self.helloworld_address,
-            &self.timelock_address,
             timestamp,
+            memo,
+        );
+
+        self.process_ix(instruction, &vec![]).await
+    }
+
+    pub async fn transfer_checked(
+        &mut self,
+        new_authority_keypair: &Keypair,
+        timestamp: i64,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::transfer_checked_ix(
+            &new_authority_keypair.pubkey(),
+            &self.helloworld_address,
+            timestamp,
+            None,
+        );
+
+        self.process_ix(instruction, &vec![new_authority_keypair])
+            .await
+    }
+
+    pub async fn extend_program(
+        &mut self,
+        new_authority_keypair: &Keypair,
+        timestamp: i64,
+        additional_bytes: u32,
+    ) -> Result<(), BanksClientError> {
+        let instruction = program_authority_client::timelock_extend_program_ix(
+            &new_authority_keypair.pubkey(),
+            &self.context.payer.pubkey(),
+            &self.helloworld_address,
+            timestamp,
+            additional_bytes,
+        );
+
+        self.process_ix(instruction, &vec![new_authority_keypair])
+            .await
+    }
+
+    pub async fn get_program_data_len(&mut self) -> usize {
+        let program_data = Pubkey::find_program_address(
+            &[self.helloworld_address.as_ref()],
+            &bpf_loader_upgradeable::id(),
         )
-        .to_account_metas(None);
+        .0;
 
-        let instruction = Instruction {
-            program_id: self.timelock_address,
-            accounts:   account_metas,
-            data:       instruction::Transfer { timestamp }.data(),
-        };
+        self.context
+            .banks_client
+            .get_account(program_data)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .len()
+    }
 
-        self.process_ix(instruction, &vec![]).await
+    /// Simulates `status` and decodes who controls the program
+    pub async fn status(
+        &mut self,
+        candidates: Vec<Pubkey>,
+        timestamps: Vec<i64>,
+        nonces: Vec<u64>,
+    ) -> Result<AuthorityStatus, BanksClientError> {
+        let instruction = self.status_instruction(candidates, timestamps, nonces);
+
+        // The bank trims the trailing zeros of return data, they are added back for Borsh
+        let mut data = self.simulate_ix(instruction, &vec![]).await?.unwrap().data;
+        data.resize(MAX_RETURN_DATA, 0);
+        Ok(AuthorityStatus::deserialize(&mut data.as_slice()).unwrap())
+    }
+
+    /// Same as `status` under a limit of `STATUS_COMPUTE_UNIT_LIMIT`, along with the compute units
+    /// it consumed
+    pub async fn status_with_units(
+        &mut self,
+        candidates: Vec<Pubkey>,
+        timestamps: Vec<i64>,
+        nonces: Vec<u64>,
+    ) -> Result<(AuthorityStatus, u64), BanksClientError> {
+        let instruction = self.status_instruction(candidates, timestamps, nonces);
+
+        let (return_data, units_consumed) = self
+            .simulate_ixs(
+                &[
+                    ComputeBudgetInstruction::set_compute_unit_limit(STATUS_COMPUTE_UNIT_LIMIT),
+                    instruction,
+                ],
+                &vec![],
+            )
+            .await?;
+        let mut data = return_data.unwrap().data;
+        data.resize(MAX_RETURN_DATA, 0);
+        Ok((
+            AuthorityStatus::deserialize(&mut data.as_slice()).unwrap(),
+            units_consumed,
+        ))
+    }
+
+    fn status_instruction(
+        &self,
+        candidates: Vec<Pubkey>,
+        timestamps: Vec<i64>,
+        nonces: Vec<u64>,
+    ) -> Instruction {
+        program_authority_client::timelock_status_ix(
+            &self.helloworld_address,
+            candidates,
+            timestamps,
+            nonces,
+        )
+    }
+
+    pub fn get_program_data_address(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[self.helloworld_address.as_ref()],
+            &bpf_loader_upgradeable::id(),
+        )
+        .0
     }
 
     pub async fn get_program_data(&mut self) -> ProgramData {
@@ -212,6 +488,40 @@ impl TimelockSimulator {
         .0
     }
 
+    pub fn get_commitment_record_address(&self, new_authority: &Pubkey, timestamp: i64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                COMMITMENT_RECORD_SEED,
+                self.get_escrow_authority(new_authority, timestamp).as_ref(),
+                self.helloworld_address.as_ref(),
+            ],
+            &self.timelock_address,
+        )
+        .0
+    }
+
+    pub async fn get_commitment_record(
+        &mut self,
+        new_authority: &Pubkey,
+        timestamp: i64,
+    ) -> Option<CommitmentRecord> {
+        let address = self.get_commitment_record_address(new_authority, timestamp);
+        self.context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .map(|account| CommitmentRecord::try_deserialize(&mut account.data.as_slice()).unwrap())
+    }
+
+    pub async fn get_balance(&mut self, address: &Pubkey) -> u64 {
+        self.context
+            .banks_client
+            .get_balance(*address)
+            .await
+            .unwrap()
+    }
+
     pub async fn warp_to_timestamp(&mut self, timestamp: i64) -> Result<(), ProgramTestError> {
         let current_clock = self
             .context
@@ -234,59 +544,3 @@ impl TimelockSimulator {
         );
     }
 }
-
-impl crate::accounts::Commit {
-    pub fn create(
-        current_authority: &Pubkey,
-        new_authority: &Pubkey,
-        program_account: &Pubkey,
-        escrow_address: &Pubkey,
-        timestamp: i64,
-    ) -> Self {
-        let escrow_authority = Pubkey::find_program_address(
-            &[new_authority.as_ref(), timestamp.to_be_bytes().as_ref()],
-            escrow_address,
-        )
-        .0;
-        let program_data = Pubkey::find_program_address(
-            &[program_account.as_ref()],
-            &bpf_loader_upgradeable::id(),
-        )
-        .0;
-        crate::accounts::Commit {
-            current_authority: *current_authority,
-            new_authority: *new_authority,
-            escrow_authority,
-            program_account: *program_account,
-            program_data,
-            bpf_upgradable_loader: bpf_loader_upgradeable::id(),
-        }
-    }
-}
-
-impl crate::accounts::Transfer {
-    pub fn create(
-        new_authority: &Pubkey,
-        program_account: &Pubkey,
-        escrow_address: &Pubkey,
-        timestamp: i64,
-    ) -> Self {
-        let escrow_authority = Pubkey::find_program_address(
-            &[new_authority.as_ref(), timestamp.to_be_bytes().as_ref()],
-            escrow_address,
-        )
-        .0;
-        let program_data = Pubkey::find_program_address(
-            &[program_account.as_ref()],
-            &bpf_loader_upgradeable::id(),
-        )
-        .0;
-        crate::accounts::Transfer {
-            new_authority: *new_authority,
-            escrow_authority,
-            program_account: *program_account,
-            program_data,
-            bpf_upgradable_loader: bpf_loader_upgradeable::id(),
-        }
-    }
-}
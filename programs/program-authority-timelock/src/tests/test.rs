@@ -1,11 +1,26 @@
 use {
     crate::{
         tests::simulator::TimelockSimulator,
+        AuthorityStatus,
+        CommitmentRecord,
         ErrorCode,
+        HandoverMemo,
+        TimelockState,
+        MAX_REASON_LEN,
+        MAX_STATUS_CANDIDATES,
+        MAX_STATUS_NONCES,
+        MAX_STATUS_TIMESTAMPS,
+        STATUS_COMPUTE_UNIT_LIMIT,
+    },
+    anchor_lang::prelude::{
+        ProgramError,
+        Pubkey,
+        Rent,
     },
-    anchor_lang::prelude::ProgramError,
     solana_sdk::{
+        feature_set::enable_bpf_loader_set_authority_checked_ix,
         instruction::InstructionError,
+        native_token::LAMPORTS_PER_SOL,
         signature::Keypair,
         signer::Signer,
         transaction::TransactionError,
@@ -108,3 +123,438 @@ async fn test() {
         .check_program_authority_matches(&authority_keypair_1.pubkey())
         .await;
 }
+
+#[tokio::test]
+async fn test_extend_program() {
+    let (mut simulator, authority_keypair_1) = TimelockSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+
+    // The program can only be extended through the timelock once it is timelocked
+    simulator.warp_to_timestamp(1700000000).await.unwrap();
+    assert_eq!(
+        simulator
+            .extend_program(&authority_keypair_2, 1700000000 + 30, 1024)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::ProgramNotTimelocked.into()
+    );
+
+    simulator
+        .commit(
+            &authority_keypair_1,
+            &authority_keypair_2.pubkey(),
+            1700000000 + 30,
+        )
+        .await
+        .unwrap();
+
+    // The timestamp has to be the one of the commitment
+    assert_eq!(
+        simulator
+            .extend_program(&authority_keypair_2, 1700000000 + 31, 1024)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::ProgramNotTimelocked.into()
+    );
+
+    let program_data_len = simulator.get_program_data_len().await;
+    simulator
+        .extend_program(&authority_keypair_2, 1700000000 + 30, 1024)
+        .await
+        .unwrap();
+    assert_eq!(
+        simulator.get_program_data_len().await,
+        program_data_len + 1024
+    );
+    simulator
+        .check_program_authority_matches(
+            &simulator.get_escrow_authority(&authority_keypair_2.pubkey(), 1700000000 + 30),
+        )
+        .await;
+}
+
+#[tokio::test]
+async fn test_new_authority_checks() {
+    let (mut simulator, authority_keypair_1) = TimelockSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+
+    assert_eq!(
+        simulator
+            .commit(&authority_keypair_1, &Pubkey::default(), 0)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::NewAuthorityIsSystemProgram.into()
+    );
+    assert_eq!(
+        simulator
+            .commit(&authority_keypair_1, &crate::id(), 0)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::NewAuthorityIsTimelockProgram.into()
+    );
+    assert_eq!(
+        simulator
+            .commit(&authority_keypair_1, &authority_keypair_1.pubkey(), 0)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::SameAuthority.into()
+    );
+
+    // A PDA nobody owns is rejected in strict mode
+    let unowned_pda = simulator.get_escrow_authority(&authority_keypair_2.pubkey(), 0);
+    assert_eq!(
+        simulator
+            .commit_strict(&authority_keypair_1, &unowned_pda, 0)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::NewAuthorityNotAllowed.into()
+    );
+
+    simulator
+        .commit_strict(&authority_keypair_1, &authority_keypair_2.pubkey(), 0)
+        .await
+        .unwrap();
+    simulator
+        .check_program_authority_matches(
+            &simulator.get_escrow_authority(&authority_keypair_2.pubkey(), 0),
+        )
+        .await;
+}
+
+#[tokio::test]
+async fn test_return_data() {
+    let (mut simulator, authority_keypair_1) = TimelockSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+
+    simulator.warp_to_timestamp(1700000000).await.unwrap();
+
+    let (escrow_authority, bump) = Pubkey::find_program_address(
+        &[
+            authority_keypair_2.pubkey().as_ref(),
+            (1700000000i64 + 30).to_be_bytes().as_ref(),
+        ],
+        &crate::id(),
+    );
+    assert_eq!(
+        simulator
+            .simulate_commit(
+                &authority_keypair_1,
+                &authority_keypair_2.pubkey(),
+                1700000000 + 30
+            )
+            .await
+            .unwrap(),
+        TimelockState {
+            escrow_authority,
+            bump,
+            program_data: simulator.get_program_data_address(),
+            unlock_timestamp: 1700000000 + 30,
+        }
+    );
+
+    // Simulating doesn't move the authority
+    simulator
+        .check_program_authority_matches(&authority_keypair_1.pubkey())
+        .await;
+}
+
+#[tokio::test]
+async fn test_status() {
+    let (mut simulator, authority_keypair_1) = TimelockSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+    let candidates = vec![authority_keypair_1.pubkey(), authority_keypair_2.pubkey()];
+
+    simulator.warp_to_timestamp(1700000000).await.unwrap();
+
+    assert_eq!(
+        simulator
+            .status(candidates.clone(), vec![1700000000 + 30], vec![])
+            .await
+            .unwrap(),
+        AuthorityStatus::Direct(authority_keypair_1.pubkey())
+    );
+
+    simulator
+        .commit(
+            &authority_keypair_1,
+            &authority_keypair_2.pubkey(),
+            1700000000 + 30,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        simulator
+            .status(
+                candidates.clone(),
+                vec![1700000000, 1700000000 + 30],
+                vec![]
+            )
+            .await
+            .unwrap(),
+        AuthorityStatus::Timelocked {
+            new_authority: authority_keypair_2.pubkey(),
+            timestamp:     1700000000 + 30,
+            matured:       false,
+        }
+    );
+
+    simulator.warp_to_timestamp(1700000000 + 31).await.unwrap();
+    assert_eq!(
+        simulator
+            .status(candidates.clone(), vec![1700000000 + 30], vec![])
+            .await
+            .unwrap(),
+        AuthorityStatus::Timelocked {
+            new_authority: authority_keypair_2.pubkey(),
+            timestamp:     1700000000 + 30,
+            matured:       true,
+        }
+    );
+
+    simulator
+        .transfer(&authority_keypair_2.pubkey(), 1700000000 + 30)
+        .await
+        .unwrap();
+    assert_eq!(
+        simulator
+            .status(candidates.clone(), vec![], vec![])
+            .await
+            .unwrap(),
+        AuthorityStatus::Direct(authority_keypair_2.pubkey())
+    );
+
+    assert_eq!(
+        simulator
+            .status(candidates, vec![0; MAX_STATUS_TIMESTAMPS + 1], vec![])
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::TooManyStatusCandidates.into()
+    );
+}
+
+#[tokio::test]
+async fn test_transfer_checked() {
+    let (mut simulator, authority_keypair_1) = TimelockSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+
+    simulator
+        .commit(&authority_keypair_1, &authority_keypair_2.pubkey(), 0)
+        .await
+        .unwrap();
+
+    simulator
+        .transfer_checked(&authority_keypair_2, 0)
+        .await
+        .unwrap();
+    simulator
+        .check_program_authority_matches(&authority_keypair_2.pubkey())
+        .await;
+}
+
+#[tokio::test]
+async fn test_transfer_checked_feature_inactive() {
+    let (mut simulator, authority_keypair_1) = TimelockSimulator::new_with_deactivated_features(&[
+        enable_bpf_loader_set_authority_checked_ix::id(),
+    ])
+    .await;
+    let authority_keypair_2 = Keypair::new();
+
+    simulator
+        .commit(&authority_keypair_1, &authority_keypair_2.pubkey(), 0)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        simulator
+            .transfer_checked(&authority_keypair_2, 0)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidInstructionData)
+    );
+    simulator
+        .check_program_authority_matches(
+            &simulator.get_escrow_authority(&authority_keypair_2.pubkey(), 0),
+        )
+        .await;
+
+    // The unchecked instruction keeps working on older runtimes
+    simulator
+        .transfer(&authority_keypair_2.pubkey(), 0)
+        .await
+        .unwrap();
+    simulator
+        .check_program_authority_matches(&authority_keypair_2.pubkey())
+        .await;
+}
+
+#[tokio::test]
+async fn test_memo() {
+    let (mut simulator, authority_keypair_1) = TimelockSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+
+    assert_eq!(
+        simulator
+            .commit_with_memo(
+                &authority_keypair_1,
+                &authority_keypair_2.pubkey(),
+                0,
+                Some(HandoverMemo::Reason(String::new()))
+            )
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::EmptyReason.into()
+    );
+    assert_eq!(
+        simulator
+            .commit_with_memo(
+                &authority_keypair_1,
+                &authority_keypair_2.pubkey(),
+                0,
+                Some(HandoverMemo::Reason("a".repeat(MAX_REASON_LEN + 1)))
+            )
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::ReasonTooLong.into()
+    );
+    simulator
+        .check_program_authority_matches(&authority_keypair_1.pubkey())
+        .await;
+
+    simulator
+        .commit_with_memo(
+            &authority_keypair_1,
+            &authority_keypair_2.pubkey(),
+            0,
+            Some(HandoverMemo::Reason("a".repeat(MAX_REASON_LEN))),
+        )
+        .await
+        .unwrap();
+    simulator
+        .check_program_authority_matches(
+            &simulator.get_escrow_authority(&authority_keypair_2.pubkey(), 0),
+        )
+        .await;
+
+    simulator
+        .transfer_with_memo(
+            &authority_keypair_2.pubkey(),
+            0,
+            Some(HandoverMemo::Hash([7; 32])),
+        )
+        .await
+        .unwrap();
+    simulator
+        .check_program_authority_matches(&authority_keypair_2.pubkey())
+        .await;
+}
+
+#[tokio::test]
+async fn test_commitment_record() {
+    let (mut simulator, authority_keypair_1) = TimelockSimulator::new().await;
+    let authority_keypair_2 = Keypair::new();
+    let payer_keypair = simulator.create_payer().await;
+
+    simulator.warp_to_timestamp(1700000000).await.unwrap();
+
+    // The current authority holds no SOL, the payer covers the rent
+    simulator
+        .commit_with_record(
+            &authority_keypair_1,
+            &authority_keypair_2.pubkey(),
+            1700000000 + 30,
+            &payer_keypair,
+            Some(HandoverMemo::Hash([7; 32])),
+        )
+        .await
+        .unwrap();
+    simulator
+        .check_program_authority_matches(
+            &simulator.get_escrow_authority(&authority_keypair_2.pubkey(), 1700000000 + 30),
+        )
+        .await;
+
+    let record = simulator
+        .get_commitment_record(&authority_keypair_2.pubkey(), 1700000000 + 30)
+        .await
+        .unwrap();
+    assert_eq!(record.current_authority, authority_keypair_1.pubkey());
+    assert_eq!(record.new_authority, authority_keypair_2.pubkey());
+    assert_eq!(record.rent_payer, payer_keypair.pubkey());
+    assert_eq!(record.unlock_timestamp, 1700000000 + 30);
+    assert_eq!(record.created_at, 1700000000);
+    assert_eq!(record.memo, Some(HandoverMemo::Hash([7; 32])));
+    assert_eq!(
+        simulator.get_balance(&payer_keypair.pubkey()).await,
+        LAMPORTS_PER_SOL - Rent::default().minimum_balance(CommitmentRecord::LEN)
+    );
+
+    assert_eq!(
+        simulator
+            .close_commitment_record(
+                &authority_keypair_2.pubkey(),
+                1700000000 + 30,
+                &payer_keypair.pubkey()
+            )
+            .await
+            .unwrap_err()
+            .unwrap(),
+        ErrorCode::CommitmentStillPending.into()
+    );
+
+    simulator.warp_to_timestamp(1700000000 + 31).await.unwrap();
+    simulator
+        .transfer(&authority_keypair_2.pubkey(), 1700000000 + 30)
+        .await
+        .unwrap();
+    simulator
+        .close_commitment_record(
+            &authority_keypair_2.pubkey(),
+            1700000000 + 30,
+            &payer_keypair.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    assert!(simulator
+        .get_commitment_record(&authority_keypair_2.pubkey(), 1700000000 + 30)
+        .await
+        .is_none());
+    assert_eq!(
+        simulator.get_balance(&payer_keypair.pubkey()).await,
+        LAMPORTS_PER_SOL
+    );
+}
+
+#[tokio::test]
+async fn test_status_compute_budget() {
+    let (mut simulator, authority_keypair) = TimelockSimulator::new().await;
+
+    // Nothing matches, so `status` derives every address its caps allow
+    let candidates = (0..MAX_STATUS_CANDIDATES)
+        .map(|_| Pubkey::new_unique())
+        .collect();
+    let timestamps = (0..MAX_STATUS_TIMESTAMPS as i64).collect();
+    let nonces = (0..MAX_STATUS_NONCES as u64).collect();
+    let (status, units_consumed) = simulator
+        .status_with_units(candidates, timestamps, nonces)
+        .await
+        .unwrap();
+    assert_eq!(status, AuthorityStatus::Direct(authority_keypair.pubkey()));
+    assert!(units_consumed < u64::from(STATUS_COMPUTE_UNIT_LIMIT));
+}
+
+#[test]
+fn test_common_program_id() {
+    // `status` derives timelock addresses from the id in the common crate
+    assert_eq!(crate::ID, program_authority_common::timelock_program::ID);
+}